@@ -6,7 +6,7 @@ __version__ = '0.1.0'
 
 
 import docprompt.silenceable_tqdm  # noqa
-from docprompt.schema.document import Document
+from docprompt.schema.document import Document, DocumentPage
 from docprompt.schema.layout import Geometry, NormBBox, TextBlock
 from docprompt.utils import load_document
 
@@ -1,25 +1,350 @@
+import os
+import re
+import signal
 import tempfile
+import time
+from dataclasses import dataclass
+from io import BytesIO
 from os import PathLike
 from pathlib import Path
-from subprocess import PIPE, CompletedProcess, run
-from typing import Dict, List, Literal, Optional, Union
+from subprocess import PIPE, CompletedProcess, Popen, TimeoutExpired, run
+from threading import Event
+from typing import Dict, Iterable, List, Literal, Optional, Tuple, Union
 
 GS = "gs"
 
+# How often a running process checks whether it has been cancelled, in seconds
+POLL_INTERVAL = 0.1
+
+# The default time limit for a Ghostscript process, in seconds. Large documents finish well
+# within it, while damaged ones that send Ghostscript into a loop are killed.
+DEFAULT_TIMEOUT = 10 * 60
+
+
+def parse_stderr(stderr: Optional[bytes]) -> List[str]:
+    """
+    Returns the distinct messages Ghostscript printed to stderr, without the asterisks and
+    indentation it decorates them with
+    """
+    messages = []
+
+    for line in (stderr or b"").decode(errors="replace").splitlines():
+        line = line.strip().strip("*").strip()
+
+        if line and line not in messages:
+            messages.append(line)
+
+    return messages
+
+
+GhostscriptErrorCategory = Literal[
+    "missing_font",
+    "damaged_xref",
+    "unsupported_feature",
+    "security",
+    "out_of_memory",
+    "timeout",
+    "cancelled",
+    "unknown",
+]
+
+# Checked in order against the lowercased message, so more specific patterns come first
+ERROR_PATTERNS: List[Tuple[GhostscriptErrorCategory, re.Pattern]] = [
+    ("out_of_memory", re.compile(r"vmerror|out of memory|cannot allocate|memory exhausted|allocation failed")),
+    ("security", re.compile(r"invalidaccess|invalidfileaccess|password|encrypt|permission|safer")),
+    (
+        "missing_font",
+        re.compile(r"can't find (\(or can't open\) )?font|font .*not found|substituting font|unable to load .*font"),
+    ),
+    (
+        "damaged_xref",
+        re.compile(
+            r"xref|cross-reference|trailer|can't find root|couldn't find .*object|rebuilding|repaired|has been damaged"
+        ),
+    ),
+    (
+        "unsupported_feature",
+        re.compile(r"unsupported|not supported|not implemented|unknown filter|unrecognized|jbig2|jpx"),
+    ),
+]
+
+PAGE_PATTERN = re.compile(r"\bpage (\d+)\b", re.IGNORECASE)
+
+
+@dataclass
+class GhostscriptIssue:
+    """
+    A message from Ghostscript's stderr, and what kind of problem it describes
+    """
+
+    message: str
+    category: GhostscriptErrorCategory
+    page_number: Optional[int] = None
+
+
+def classify_message(message: str) -> GhostscriptIssue:
+    category: GhostscriptErrorCategory = "unknown"
+
+    for pattern_category, pattern in ERROR_PATTERNS:
+        if pattern.search(message.lower()):
+            category = pattern_category
+            break
+
+    page_match = PAGE_PATTERN.search(message)
+
+    return GhostscriptIssue(
+        message=message, category=category, page_number=int(page_match.group(1)) if page_match else None
+    )
+
 
 class GhostscriptError(Exception):
-    def __init__(self, message: str, process: CompletedProcess) -> None:
+    """
+    Raised when Ghostscript fails. `issues` classifies what it printed to stderr, `category`
+    is the most likely cause, `page_number` is the page it failed on if that's known, and
+    `output_produced` is whether it wrote any output before failing.
+    """
+
+    def __init__(
+        self,
+        message: str,
+        process: CompletedProcess,
+        *,
+        page_number: Optional[int] = None,
+        output_produced: Optional[bool] = None,
+    ) -> None:
+        self.message = message
         self.process = process
+        self.messages = parse_stderr(process.stderr)
+        self.issues = [classify_message(message) for message in self.messages]
+
+        if page_number is None:
+            page_number = next((issue.page_number for issue in self.issues if issue.page_number), None)
+
+        self.page_number = page_number
+        self.output_produced = bool(process.stdout) if output_produced is None else output_produced
+
         super().__init__(message)
 
+    @property
+    def primary_issue(self) -> Optional[GhostscriptIssue]:
+        """
+        The first issue with a known cause, or the first issue if none has one
+        """
+        return next((issue for issue in self.issues if issue.category != "unknown"), None) or next(
+            iter(self.issues), None
+        )
+
+    @property
+    def category(self) -> GhostscriptErrorCategory:
+        issue = self.primary_issue
+
+        return issue.category if issue else "unknown"
+
+    def __str__(self) -> str:
+        message = self.message
+
+        if self.page_number is not None:
+            message += f" on page {self.page_number}"
+
+        if self.primary_issue:
+            message += f": {self.primary_issue.message}"
+        elif self.process.returncode:
+            message += f" (exit code {self.process.returncode})"
+
+        return message
+
+
+class GhostscriptTimeoutError(GhostscriptError):
+    """
+    Raised when Ghostscript runs longer than its timeout, and was killed
+    """
+
+    def __init__(self, message: str, process: CompletedProcess, timeout: float, **kwargs) -> None:
+        self.timeout = timeout
+        super().__init__(message, process, **kwargs)
+
+    @property
+    def category(self) -> GhostscriptErrorCategory:
+        return "timeout"
+
+
+class GhostscriptCancelledError(GhostscriptError):
+    """
+    Raised when Ghostscript was killed because its work was cancelled
+    """
+
+    @property
+    def category(self) -> GhostscriptErrorCategory:
+        return "cancelled"
+
+
+@dataclass
+class ProcessLimits:
+    """
+    Bounds on a Ghostscript process. `timeout` is in seconds and `max_memory` is the most
+    virtual memory it may use, in bytes. Setting `cancel_event` kills the process.
+    """
+
+    timeout: Optional[float] = None
+    max_memory: Optional[int] = None
+    cancel_event: Optional[Event] = None
+
+
+def _kill(process: Popen) -> None:
+    if os.name != "posix":
+        process.kill()
+        return
+
+    try:
+        os.killpg(process.pid, signal.SIGKILL)
+    except ProcessLookupError:  # Exited in the meantime
+        pass
+
+
+def _run(args: List[str], limits: Optional[ProcessLimits] = None) -> CompletedProcess:
+    if limits is None or (limits.timeout is None and limits.max_memory is None and limits.cancel_event is None):
+        return run(args, stdout=PIPE, stderr=PIPE, check=False)
+
+    if limits.cancel_event is not None and limits.cancel_event.is_set():
+        raise GhostscriptCancelledError("Ghostscript was cancelled", CompletedProcess(args, -1, b"", b""))
+
+    if limits.max_memory is not None and os.name == "posix":
+        # Setting the limit from preexec_fn isn't safe when other threads are running, so let the shell do it
+        args = ["sh", "-c", f'ulimit -v {limits.max_memory // 1024} && exec "$@"', GS] + args
+
+    deadline = time.monotonic() + limits.timeout if limits.timeout is not None else None
+
+    # A new session lets the whole process group be killed, including the shell wrapping it
+    with Popen(args, stdout=PIPE, stderr=PIPE, start_new_session=os.name == "posix") as process:
+        while True:
+            wait = POLL_INTERVAL if limits.cancel_event is not None else None
+
+            if deadline is not None:
+                wait = min(wait or limits.timeout, max(0.0, deadline - time.monotonic()))
+
+            try:
+                stdout, stderr = process.communicate(timeout=wait)
+                break
+            except TimeoutExpired:
+                timed_out = deadline is not None and time.monotonic() >= deadline
+                cancelled = limits.cancel_event is not None and limits.cancel_event.is_set()
+
+                if not (timed_out or cancelled):
+                    continue
+
+                _kill(process)
+                stdout, stderr = process.communicate()
+                result = CompletedProcess(args, process.returncode, stdout, stderr)
+
+                if cancelled:
+                    raise GhostscriptCancelledError("Ghostscript was cancelled", result)
+
+                raise GhostscriptTimeoutError(
+                    f"Ghostscript timed out after {limits.timeout} seconds", result, limits.timeout
+                )
+
+    return CompletedProcess(args, process.returncode, stdout, stderr)
+
+
+DEVICE_FORMATS: Dict[str, str] = {
+    "pnggray": "png",
+    "png16m": "png",
+    "png256": "png",
+    "pngmono": "png",
+    "jpeg": "jpeg",
+    "jpeggray": "jpeg",
+    "tiffg4": "tiff",
+    "tifflzw": "tiff",
+    "tiffgray": "tiff",
+    "tiff24nc": "tiff",
+    "webp": "webp",
+}
+
+PNG_SIGNATURE = b'\x89PNG\r\n\x1a\n'
+JPEG_SOI = b'\xff\xd8\xff'
+
 
 def _validate_device(device: str) -> str:
-    if device not in ["pnggray", "png16m", "png256"]:
-        raise ValueError("Invalid device")
+    if device not in DEVICE_FORMATS:
+        raise ValueError(f"Invalid device {device}, must be one of {list(DEVICE_FORMATS)}")
 
     return device
 
 
+def _get_device_args(device: str, quality: Optional[int]) -> List[str]:
+    if device == "webp":
+        # Ghostscript has no WebP device, so pages are rendered to PNG and converted afterwards
+        device = "png16m"
+
+    args = [f"-sDEVICE={device}"]
+
+    if device in ["jpeg", "jpeggray"] and quality is not None:
+        args += [f"-dJPEGQ={quality}"]
+
+    if device in ["tiffgray", "tiff24nc"]:
+        # tiffg4 and tifflzw are bilevel and compressed by definition, but these default to uncompressed
+        args += ["-sCompression=lzw"]
+
+    return args
+
+
+def _convert_to_webp(data: bytes, quality: Optional[int]) -> bytes:
+    from PIL import Image
+
+    output = BytesIO()
+    Image.open(BytesIO(data)).save(output, format="WEBP", **({"quality": quality} if quality is not None else {}))
+
+    return output.getvalue()
+
+
+def _count_written_pages(process: CompletedProcess, output_path: str, device: str) -> Optional[int]:
+    """
+    Returns how many pages Ghostscript wrote, or None if they all go to one file
+    """
+    if DEVICE_FORMATS[device] == "tiff" and "%d" not in output_path:
+        return None
+
+    if output_path == "%stdout":
+        return len(split_images(process.stdout or b"", device))
+
+    if "%d" in output_path:
+        count = 0
+
+        while Path(output_path % (count + 1)).exists():
+            count += 1
+
+        return count
+
+    return None
+
+
+def _set_page_context(
+    error: GhostscriptError, output_path: str, device: str, first_page: int, last_page: Optional[int]
+) -> GhostscriptError:
+    """
+    Works out which page Ghostscript failed on, and whether it wrote anything first. Pages are
+    written in order as soon as they are rendered, so the failed page is the one after the last
+    page written.
+    """
+    written = _count_written_pages(error.process, output_path, device)
+
+    if written is None:
+        if output_path == "%stdout":
+            error.output_produced = bool(error.process.stdout)
+        else:
+            error.output_produced = Path(output_path).exists() and Path(output_path).stat().st_size > 0
+
+        return error
+
+    error.output_produced = written > 0
+
+    # If every page was written, it failed while finishing up rather than on a page
+    if last_page is None or first_page + written <= last_page:
+        error.page_number = first_page + written
+
+    return error
+
+
 def rasterize_page(
     fp: Union[PathLike, str],
     output_path: str,
@@ -27,7 +352,14 @@ def rasterize_page(
     *,
     dpi: int = 200,
     device="pnggray",
+    downscale_factor: Optional[int] = None,
+    quality: Optional[int] = None,
+    limits: Optional[ProcessLimits] = None,
 ):
+    """
+    Rasterizes a single page. For the `webp` device, the output is a PNG which the `_to_bytes`
+    and `_to_path` functions convert.
+    """
     device = _validate_device(device)
     args = [
         GS,
@@ -35,10 +367,16 @@ def rasterize_page(
         "-dSAFER",
         "-dBATCH",
         "-dNOPAUSE",
-        "-sDEVICE=png16m",
         "-dTextAlphaBits=4",
         "-dGraphicsAlphaBits=4",
-        f"-sDEVICE={device}",
+        "-dUseCropBox",  # Render the visible region of the page, which is what normalized coordinates refer to
+    ]
+
+    if downscale_factor is not None:
+        args += [f"-dDownScaleFactor={downscale_factor}"]
+
+    args += _get_device_args(device, quality)
+    args += [
         f"-dFirstPage={idx}",
         f"-dLastPage={idx}",
         f"-r{dpi}",
@@ -47,10 +385,15 @@ def rasterize_page(
         str(fp),
     ]
 
-    result = run(args, stdout=PIPE, stderr=PIPE, check=False)
+    try:
+        result = _run(args, limits)
+    except GhostscriptError as e:  # Timed out or cancelled
+        _set_page_context(e, output_path, device, idx, idx)
+        raise
 
     if result.returncode != 0:
-        raise GhostscriptError("Ghostscript failed to rasterize the document: ", result)
+        error = GhostscriptError("Ghostscript failed to rasterize the document", result)
+        raise _set_page_context(error, output_path, device, idx, idx)
 
     return result
 
@@ -62,6 +405,10 @@ def rasterize_pdf(
     dpi: int = 100,
     device="pnggray",
     downscale_factor: Optional[int] = None,
+    quality: Optional[int] = None,
+    first_page: Optional[int] = None,
+    last_page: Optional[int] = None,
+    limits: Optional[ProcessLimits] = None,
 ):
     device = _validate_device(device)
     base_args = [
@@ -73,41 +420,52 @@ def rasterize_pdf(
         "-dTextAlphaBits=4",
         "-dGraphicsAlphaBits=4",
         "-dBufferSpace=250000000",  # 250 Mb of buffer space.
+        "-dUseCropBox",
     ]
 
     if downscale_factor is not None:
         base_args += [f"-dDownScaleFactor={downscale_factor}"]
 
-    args = base_args + [
-        f"-sDEVICE={device}",
+    args = base_args + _get_device_args(device, quality)
+
+    if first_page is not None:
+        args += [f"-dFirstPage={first_page}"]
+
+    if last_page is not None:
+        args += [f"-dLastPage={last_page}"]
+
+    args += [
         f"-r{dpi}",
         f"-sOutputFile={output_path}",
         "-f",
         str(fp),
     ]
 
-    result = run(args, stdout=PIPE, stderr=PIPE, check=False)
+    try:
+        result = _run(args, limits)
+    except GhostscriptError as e:  # Timed out or cancelled
+        _set_page_context(e, output_path, device, first_page or 1, last_page)
+        raise
 
     if result.returncode != 0:
-        raise GhostscriptError("Ghostscript failed to rasterize the document: ", result)
+        error = GhostscriptError("Ghostscript failed to rasterize the document", result)
+        raise _set_page_context(error, output_path, device, first_page or 1, last_page)
 
     return result
 
 
 def split_png_images(data: bytes) -> List[bytes]:
-    # PNG signature
-    png_signature = b'\x89PNG\r\n\x1a\n'
     images = []
 
     # Find the first PNG signature
-    start = data.find(png_signature)
+    start = data.find(PNG_SIGNATURE)
     if start == -1:
         # No PNG images found in the data
         return []
 
     while True:
         # Find the next PNG signature in the data
-        next_start = data.find(png_signature, start + 1)
+        next_start = data.find(PNG_SIGNATURE, start + 1)
         if next_start == -1:
             # No more images
             images.append(data[start:])
@@ -118,29 +476,248 @@ def split_png_images(data: bytes) -> List[bytes]:
     return images
 
 
+def _find_jpeg_end(data: bytes, start: int) -> int:
+    """
+    Returns the offset just past the end of the JPEG starting at `start`, by walking its
+    segments up to the EOI marker
+    """
+    pos = start + 2
+
+    while pos + 1 < len(data):
+        if data[pos] != 0xFF:
+            pos += 1
+            continue
+
+        marker = data[pos + 1]
+
+        if marker == 0xD9:  # EOI
+            return pos + 2
+
+        if marker == 0xFF:  # Fill byte
+            pos += 1
+            continue
+
+        if 0xD0 <= marker <= 0xD7 or marker == 0x01:  # Markers without a length
+            pos += 2
+            continue
+
+        pos += 2 + int.from_bytes(data[pos + 2 : pos + 4], "big")
+
+        if marker == 0xDA:
+            # Entropy coded data follows the scan header. 0xFF bytes in it are followed by a
+            # stuffed 0x00 or a restart marker, so the next real marker is the first that isn't
+            while True:
+                pos = data.find(b'\xff', pos)
+
+                if pos == -1 or pos + 1 >= len(data):
+                    return len(data)
+
+                if data[pos + 1] == 0x00 or 0xD0 <= data[pos + 1] <= 0xD7:
+                    pos += 2
+                    continue
+
+                break
+
+    return len(data)
+
+
+def split_jpeg_images(data: bytes) -> List[bytes]:
+    # Splitting on SOI markers alone would break on JPEGs with embedded thumbnails
+    images = []
+    start = data.find(JPEG_SOI)
+
+    while start != -1:
+        end = _find_jpeg_end(data, start)
+        images.append(data[start:end])
+        start = data.find(JPEG_SOI, end)
+
+    return images
+
+
+def split_images(data: bytes, device: str) -> List[bytes]:
+    """
+    Splits the concatenated images Ghostscript writes to stdout for a multi-page document
+    """
+    image_format = DEVICE_FORMATS[_validate_device(device)]
+
+    if image_format in ["png", "webp"]:
+        return split_png_images(data)
+
+    if image_format == "jpeg":
+        return split_jpeg_images(data)
+
+    raise ValueError(f"Images from the {device} device can't be split from a stream")
+
+
 def rasterize_pdf_to_bytes(
-    fp: Union[PathLike, str], *, dpi: int = 100, device="pnggray", downscale_factor: Optional[int] = None
+    fp: Union[PathLike, str],
+    *,
+    dpi: int = 100,
+    device="pnggray",
+    downscale_factor: Optional[int] = None,
+    quality: Optional[int] = None,
+    first_page: Optional[int] = None,
+    last_page: Optional[int] = None,
+    limits: Optional[ProcessLimits] = None,
+) -> Dict[int, bytes]:
+    """
+    Rasterizes the document, or the pages from `first_page` to `last_page`, keyed by page number
+    """
+    device = _validate_device(device)
+    page_args = {"first_page": first_page, "last_page": last_page, "limits": limits}
+
+    if DEVICE_FORMATS[device] == "tiff":
+        # libtiff has to seek in its output, so TIFFs are written to a file per page instead of stdout
+        with tempfile.TemporaryDirectory() as output_dir:
+            rasterize_pdf(
+                fp,
+                str(Path(output_dir) / "page-%d.tif"),
+                dpi=dpi,
+                device=device,
+                downscale_factor=downscale_factor,
+                **page_args,
+            )
+
+            images = [
+                path.read_bytes()
+                for path in sorted(Path(output_dir).glob("page-*.tif"), key=lambda path: int(path.stem[5:]))
+            ]
+    else:
+        result = rasterize_pdf(
+            fp, "%stdout", dpi=dpi, device=device, downscale_factor=downscale_factor, quality=quality, **page_args
+        )
+
+        images = split_images(result.stdout, device)
+
+    if device == "webp":
+        images = [_convert_to_webp(image, quality) for image in images]
+
+    return {idx: image for idx, image in enumerate(images, start=first_page or 1)}
+
+
+def group_page_runs(pages: Iterable[int]) -> List[Tuple[int, int]]:
+    """
+    Groups page numbers into the fewest (first, last) runs of consecutive pages
+    """
+    runs: List[Tuple[int, int]] = []
+
+    for page_number in sorted(set(pages)):
+        if runs and runs[-1][1] == page_number - 1:
+            runs[-1] = (runs[-1][0], page_number)
+        else:
+            runs.append((page_number, page_number))
+
+    return runs
+
+
+def rasterize_pages_to_bytes(
+    fp: Union[PathLike, str],
+    pages: Iterable[int],
+    *,
+    dpi: int = 100,
+    device="pnggray",
+    downscale_factor: Optional[int] = None,
+    quality: Optional[int] = None,
+    limits: Optional[ProcessLimits] = None,
 ) -> Dict[int, bytes]:
-    if "png" not in device:
-        raise ValueError("Device must be a PNG device for rasterize_pdf_to_bytes")
+    """
+    Rasterizes a list of pages (1-indexed), keyed by page number. Ghostscript can only render a
+    range of pages per run, so consecutive pages are grouped to keep the number of runs down.
+    """
+    images = {}
+
+    for first_page, last_page in group_page_runs(pages):
+        if first_page < 1:
+            raise ValueError("Page numbers must be 1 or greater")
+
+        images.update(
+            rasterize_pdf_to_bytes(
+                fp,
+                dpi=dpi,
+                device=device,
+                downscale_factor=downscale_factor,
+                quality=quality,
+                first_page=first_page,
+                last_page=last_page,
+                limits=limits,
+            )
+        )
 
-    result = rasterize_pdf(fp, "%stdout", dpi=dpi, device=device, downscale_factor=downscale_factor)
+    return images
 
-    images = split_png_images(result.stdout)
 
-    return {idx: image for idx, image in enumerate(images, start=1)}
+def rasterize_pdf_to_tiff(
+    fp: Union[PathLike, str],
+    *,
+    dpi: int = 100,
+    device="tiffg4",
+    downscale_factor: Optional[int] = None,
+    limits: Optional[ProcessLimits] = None,
+) -> bytes:
+    """
+    Rasterizes the document to a single multi-page TIFF
+    """
+    if DEVICE_FORMATS[_validate_device(device)] != "tiff":
+        raise ValueError("Device must be a TIFF device for rasterize_pdf_to_tiff")
+
+    with tempfile.TemporaryDirectory() as output_dir:
+        output_path = Path(output_dir) / "document.tif"
+
+        rasterize_pdf(fp, str(output_path), dpi=dpi, device=device, downscale_factor=downscale_factor, limits=limits)
 
+        return output_path.read_bytes()
 
-def rasterize_page_to_bytes(fp: Union[PathLike, str], idx: int, *, dpi: int = 200, device="pnggray") -> bytes:
+
+def rasterize_page_to_bytes(
+    fp: Union[PathLike, str],
+    idx: int,
+    *,
+    dpi: int = 200,
+    device="pnggray",
+    downscale_factor: Optional[int] = None,
+    quality: Optional[int] = None,
+    limits: Optional[ProcessLimits] = None,
+) -> bytes:
     if isinstance(fp, bytes):
         with tempfile.NamedTemporaryFile(suffix=".pdf") as f:
             f.write(fp)
             f.flush()
-            result = rasterize_page(f.file.name, "%stdout", idx, dpi=dpi, device=device)
+            return rasterize_page_to_bytes(
+                f.file.name,
+                idx,
+                dpi=dpi,
+                device=device,
+                downscale_factor=downscale_factor,
+                quality=quality,
+                limits=limits,
+            )
+
+    device = _validate_device(device)
+
+    if DEVICE_FORMATS[device] == "tiff":
+        with tempfile.TemporaryDirectory() as output_dir:
+            output_path = Path(output_dir) / "page.tif"
+            rasterize_page(
+                fp, str(output_path), idx, dpi=dpi, device=device, downscale_factor=downscale_factor, limits=limits
+            )
+            data = output_path.read_bytes()
     else:
-        result = rasterize_page(fp, "%stdout", idx, dpi=dpi, device=device)
+        result = rasterize_page(
+            fp,
+            "%stdout",
+            idx,
+            dpi=dpi,
+            device=device,
+            downscale_factor=downscale_factor,
+            quality=quality,
+            limits=limits,
+        )
+        data = result.stdout
 
-    return result.stdout
+    if device == "webp":
+        data = _convert_to_webp(data, quality)
+
+    return data
 
 
 def rasterize_page_to_path(
@@ -150,14 +727,9 @@ def rasterize_page_to_path(
     *,
     dpi: int = 200,
     device="pnggray",
+    quality: Optional[int] = None,
 ) -> Path:
-    if isinstance(fp, bytes):
-        with tempfile.NamedTemporaryFile(suffix=".pdf") as f:
-            f.write(fp)
-            f.flush()
-            rasterize_page(f.file.name, str(output_path), idx, dpi=dpi, device=device)
-    else:
-        rasterize_page(fp, str(output_path), idx, dpi=dpi, device=device)
+    Path(output_path).write_bytes(rasterize_page_to_bytes(fp, idx, dpi=dpi, device=device, quality=quality))
 
     return Path(output_path)
 
@@ -167,6 +739,7 @@ def compress_pdf(
     output_path: str,
     *,
     compression: Literal["jpeg", "lossless"] = "jpeg",
+    limits: Optional[ProcessLimits] = None,
 ):
     compression_args = []
     if compression == "jpeg":
@@ -211,7 +784,7 @@ def compress_pdf(
         ]
     )
 
-    result = run(args_gs, stdout=PIPE, stderr=PIPE, check=False)
+    result = _run(args_gs, limits)
 
     if result.returncode != 0:
         raise GhostscriptError("Ghostscript failed to compress the document", result)
@@ -219,8 +792,13 @@ def compress_pdf(
     return result
 
 
-def compress_pdf_to_bytes(fp: Union[PathLike, str], *, compression: Literal["jpeg", "lossless"] = "jpeg") -> bytes:
-    result = compress_pdf(fp, output_path="%stdout", compression=compression)
+def compress_pdf_to_bytes(
+    fp: Union[PathLike, str],
+    *,
+    compression: Literal["jpeg", "lossless"] = "jpeg",
+    limits: Optional[ProcessLimits] = None,
+) -> bytes:
+    result = compress_pdf(fp, output_path="%stdout", compression=compression, limits=limits)
 
     return result.stdout
 
@@ -234,3 +812,43 @@ def compress_pdf_to_path(
     compress_pdf(fp, output_path=str(output_path), compression=compression)
 
     return Path(output_path)
+
+
+def rewrite_pdf(
+    fp: Union[PathLike, str],
+    output_path: str,
+    *,
+    limits: Optional[ProcessLimits] = None,
+):
+    """
+    Re-distills a PDF with pdfwrite, which rebuilds its object structure and cross-reference
+    table. Ghostscript recovers from many kinds of damage when interpreting a PDF, so this is
+    useful for repairing malformed files.
+    """
+    args = [
+        GS,
+        "-q",
+        "-dSAFER",
+        "-dBATCH",
+        "-dNOPAUSE",
+        "-sstdout=%stderr",  # Repair warnings are printed to stdout, which would corrupt the output
+        "-sDEVICE=pdfwrite",
+        "-dAutoRotatePages=/None",
+        "-sColorConversionStrategy=LeaveColorUnchanged",
+        f"-sOutputFile={output_path}",
+        "-f",
+        str(fp),
+    ]
+
+    result = _run(args, limits)
+
+    if result.returncode != 0:
+        raise GhostscriptError("Ghostscript failed to rewrite the document", result)
+
+    return result
+
+
+def rewrite_pdf_to_bytes(fp: Union[PathLike, str], *, limits: Optional[ProcessLimits] = None) -> bytes:
+    result = rewrite_pdf(fp, "%stdout", limits=limits)
+
+    return result.stdout
@@ -0,0 +1,75 @@
+import re
+import tempfile
+from os import PathLike
+from pathlib import Path
+from subprocess import PIPE, CompletedProcess, run
+from typing import Dict, Optional, Union
+
+PDFTOPPM = "pdftoppm"
+
+OUTPUT_PREFIX = "page"
+
+
+class PdftoppmError(Exception):
+    def __init__(self, message: str, process: CompletedProcess) -> None:
+        self.process = process
+        super().__init__(message)
+
+
+def rasterize_pdf(
+    fp: Union[PathLike, str],
+    output_prefix: str,
+    *,
+    dpi: int = 100,
+    gray: bool = False,
+    first_page: Optional[int] = None,
+    last_page: Optional[int] = None,
+):
+    args = [PDFTOPPM, "-png", "-cropbox", "-r", str(dpi)]
+
+    if gray:
+        args += ["-gray"]
+
+    if first_page is not None:
+        args += ["-f", str(first_page)]
+
+    if last_page is not None:
+        args += ["-l", str(last_page)]
+
+    args += [str(fp), output_prefix]
+
+    result = run(args, stdout=PIPE, stderr=PIPE, check=False)
+
+    if result.returncode != 0:
+        raise PdftoppmError("pdftoppm failed to rasterize the document", result)
+
+    return result
+
+
+def rasterize_pdf_to_bytes(
+    fp: Union[PathLike, str],
+    *,
+    dpi: int = 100,
+    gray: bool = False,
+    first_page: Optional[int] = None,
+    last_page: Optional[int] = None,
+) -> Dict[int, bytes]:
+    """
+    Rasterizes a range of pages to PNG, keyed by page number. pdftoppm can only write
+    multiple pages to files, so they are written to a temporary directory and read back.
+    """
+    with tempfile.TemporaryDirectory() as output_dir:
+        rasterize_pdf(
+            fp, str(Path(output_dir) / OUTPUT_PREFIX), dpi=dpi, gray=gray, first_page=first_page, last_page=last_page
+        )
+
+        images = {}
+
+        # Files are named like page-07.png, zero-padded to the number of digits of the page count
+        for path in Path(output_dir).glob(f"{OUTPUT_PREFIX}-*.png"):
+            match = re.search(r"-(\d+)\.png$", path.name)
+
+            if match:
+                images[int(match.group(1))] = path.read_bytes()
+
+    return dict(sorted(images.items()))
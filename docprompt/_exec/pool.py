@@ -0,0 +1,167 @@
+from concurrent.futures import ThreadPoolExecutor, as_completed
+from math import ceil
+from os import PathLike, cpu_count
+from threading import Event, Lock
+from typing import Dict, Iterable, List, Optional, Set, Tuple, Union
+
+from .ghostscript import ProcessLimits, group_page_runs, rasterize_pdf_to_bytes
+
+
+class GhostscriptPool:
+    """
+    Rasterizes documents by sharding their pages across several Ghostscript processes.
+
+    Each process is bound by `timeout` (in seconds) and `max_memory` (in bytes), and raises a
+    `GhostscriptTimeoutError` if it runs too long. If any shard fails, the others are killed
+    and the error is raised. `cancel` kills all work in progress from another thread.
+    """
+
+    def __init__(
+        self,
+        max_workers: Optional[int] = None,
+        *,
+        timeout: Optional[float] = None,
+        max_memory: Optional[int] = None,
+        min_pages_per_shard: int = 4,
+    ) -> None:
+        self.max_workers = max_workers or cpu_count() or 1
+        self.timeout = timeout
+        self.max_memory = max_memory
+        self.min_pages_per_shard = min_pages_per_shard
+
+        # The work happens in Ghostscript processes, so threads are enough to drive them
+        self._executor = ThreadPoolExecutor(max_workers=self.max_workers, thread_name_prefix="ghostscript")
+        self._cancel_events: Set[Event] = set()
+        self._lock = Lock()
+
+    def _shard(self, pages: Iterable[int]) -> List[Tuple[int, int]]:
+        """
+        Splits pages into (first, last) runs, sized to spread them evenly across the workers
+        without starting a process for every page
+        """
+        runs = group_page_runs(pages)
+        page_count = sum(last - first + 1 for first, last in runs)
+        shard_size = max(self.min_pages_per_shard, ceil(page_count / self.max_workers))
+
+        shards = []
+
+        for first, last in runs:
+            for start in range(first, last + 1, shard_size):
+                shards.append((start, min(start + shard_size - 1, last)))
+
+        return shards
+
+    def _rasterize_shards(
+        self,
+        fp: Union[PathLike, str],
+        shards: List[Tuple[Optional[int], Optional[int]]],
+        *,
+        dpi: int,
+        device: str,
+        downscale_factor: Optional[int],
+        quality: Optional[int],
+    ) -> Dict[int, bytes]:
+        cancel_event = Event()
+        limits = ProcessLimits(timeout=self.timeout, max_memory=self.max_memory, cancel_event=cancel_event)
+
+        with self._lock:
+            self._cancel_events.add(cancel_event)
+
+        try:
+            futures = [
+                self._executor.submit(
+                    rasterize_pdf_to_bytes,
+                    fp,
+                    dpi=dpi,
+                    device=device,
+                    downscale_factor=downscale_factor,
+                    quality=quality,
+                    first_page=first_page,
+                    last_page=last_page,
+                    limits=limits,
+                )
+                for first_page, last_page in shards
+            ]
+
+            images = {}
+
+            try:
+                for future in as_completed(futures):
+                    images.update(future.result())
+            except BaseException:
+                # Don't leave the other shards running once the result is lost anyway
+                cancel_event.set()
+
+                for future in futures:
+                    future.cancel()
+
+                raise
+
+            return dict(sorted(images.items()))
+        finally:
+            with self._lock:
+                self._cancel_events.discard(cancel_event)
+
+    def rasterize_pages(
+        self,
+        fp: Union[PathLike, str],
+        pages: Iterable[int],
+        *,
+        dpi: int = 100,
+        device="pnggray",
+        downscale_factor: Optional[int] = None,
+        quality: Optional[int] = None,
+    ) -> Dict[int, bytes]:
+        """
+        Rasterizes a list of pages (1-indexed), keyed by page number
+        """
+        pages = list(pages)
+
+        if any(page_number < 1 for page_number in pages):
+            raise ValueError("Page numbers must be 1 or greater")
+
+        return self._rasterize_shards(
+            fp, self._shard(pages), dpi=dpi, device=device, downscale_factor=downscale_factor, quality=quality
+        )
+
+    def rasterize_pdf(
+        self,
+        fp: Union[PathLike, str],
+        *,
+        dpi: int = 100,
+        device="pnggray",
+        downscale_factor: Optional[int] = None,
+        quality: Optional[int] = None,
+    ) -> Dict[int, bytes]:
+        if self.max_workers == 1:
+            # There's nothing to shard, so skip reading the page count
+            shards: List[Tuple[Optional[int], Optional[int]]] = [(None, None)]
+        else:
+            from pypdf import PdfReader
+
+            shards = self._shard(range(1, len(PdfReader(fp).pages) + 1))
+
+        return self._rasterize_shards(
+            fp, shards, dpi=dpi, device=device, downscale_factor=downscale_factor, quality=quality
+        )
+
+    def cancel(self) -> None:
+        """
+        Kills every Ghostscript process started by the pool, which makes the calls waiting on
+        them raise `GhostscriptCancelledError`
+        """
+        with self._lock:
+            for cancel_event in self._cancel_events:
+                cancel_event.set()
+
+    def shutdown(self, cancel: bool = False) -> None:
+        if cancel:
+            self.cancel()
+
+        self._executor.shutdown(wait=True, cancel_futures=cancel)
+
+    def __enter__(self) -> "GhostscriptPool":
+        return self
+
+    def __exit__(self, exc_type, exc_value, traceback) -> None:
+        self.shutdown(cancel=exc_type is not None)
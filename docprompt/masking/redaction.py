@@ -0,0 +1,266 @@
+import re
+from io import BytesIO
+from typing import TYPE_CHECKING, Dict, List, Optional, Tuple, Union
+
+from PIL import Image, ImageDraw
+from pypdf import PdfReader, PdfWriter
+
+from docprompt.schema.layout import NormBBox, TextBlock
+from docprompt.schema.operations import PageTextExtractionOutput
+from docprompt.utils.image import images_to_pdf_bytes
+
+if TYPE_CHECKING:
+    from docprompt.schema.document import Document, DocumentPage
+
+RedactionRegion = Union[TextBlock, NormBBox]
+
+REDACTION_CHARACTER = "█"
+
+DEFAULT_REDACTION_DPI = 200
+
+
+def region_to_bbox(region: RedactionRegion, padding: float = 0.0) -> NormBBox:
+    """
+    Returns a bounding box that fully covers a region. For text blocks with a bounding
+    poly, this covers every vertex so that skewed text is not partially left visible.
+    """
+    if isinstance(region, TextBlock):
+        if region.geometry.bounding_poly is not None:
+            vertices = region.geometry.bounding_poly.normalized_vertices
+            bbox = NormBBox(
+                x0=min(v.x for v in vertices),
+                top=min(v.y for v in vertices),
+                x1=max(v.x for v in vertices),
+                bottom=max(v.y for v in vertices),
+            )
+        else:
+            bbox = region.bounding_box
+    else:
+        bbox = region
+
+    return NormBBox(
+        x0=max(bbox.x0 - padding, 0.0),
+        top=max(bbox.top - padding, 0.0),
+        x1=min(bbox.x1 + padding, 1.0),
+        bottom=min(bbox.bottom + padding, 1.0),
+    )
+
+
+def get_overlap_fraction(bbox: NormBBox, region: NormBBox) -> float:
+    """
+    Returns the fraction of `bbox` that is covered by `region`
+    """
+    width = min(bbox.x1, region.x1) - max(bbox.x0, region.x0)
+    height = min(bbox.bottom, region.bottom) - max(bbox.top, region.top)
+
+    if width <= 0 or height <= 0:
+        return 0.0
+
+    if bbox.area == 0:
+        return 1.0
+
+    return (width * height) / bbox.area
+
+
+def find_pattern_matches(
+    page_text: PageTextExtractionOutput, patterns: List[Union[str, re.Pattern]]
+) -> List[TextBlock]:
+    """
+    Returns the words of a page that overlap any match of the given patterns in the page text.
+
+    Words without text spans are matched against the patterns individually.
+    """
+    compiled = [re.compile(pattern) if isinstance(pattern, str) else pattern for pattern in patterns]
+
+    match_ranges: List[Tuple[int, int]] = []
+
+    for regex in compiled:
+        for match in regex.finditer(page_text.text):
+            if match.end() > match.start():
+                match_ranges.append((match.start(), match.end()))
+
+    matched_words = []
+
+    for word in page_text.words:
+        if word.text_spans:
+            if any(
+                span.start_index < end and span.end_index > start
+                for span in word.text_spans
+                for start, end in match_ranges
+            ):
+                matched_words.append(word)
+        elif any(regex.search(word.text) for regex in compiled):
+            matched_words.append(word)
+
+    return matched_words
+
+
+def redact_page_text(page_text: PageTextExtractionOutput, regions: List[NormBBox], threshold: float = 0.5):
+    """
+    Returns a copy of a page's text extraction output with every word that is mostly
+    covered by a region replaced by redaction characters.
+
+    Replacement keeps string lengths intact, so text spans remain valid. Lines and blocks
+    are rebuilt from the redacted page text when they have spans, and are masked
+    entirely when they don't and overlap a region.
+    """
+
+    def is_redacted(block: TextBlock) -> bool:
+        return any(get_overlap_fraction(block.bounding_box, region) >= threshold for region in regions)
+
+    def mask(text: str) -> str:
+        return "".join(c if c.isspace() else REDACTION_CHARACTER for c in text)
+
+    page_chars = list(page_text.text)
+    words = []
+
+    for word in page_text.words:
+        word = word.model_copy(deep=True)
+
+        if is_redacted(word):
+            word.text = mask(word.text)
+
+            for span in word.text_spans or []:
+                for index in range(span.start_index, min(span.end_index, len(page_chars))):
+                    if not page_chars[index].isspace():
+                        page_chars[index] = REDACTION_CHARACTER
+
+        words.append(word)
+
+    redacted_text = "".join(page_chars)
+
+    def redact_container(block: TextBlock) -> TextBlock:
+        block = block.model_copy(deep=True)
+
+        if block.text_spans:
+            block.text = "".join(redacted_text[span.start_index : span.end_index] for span in block.text_spans)
+        elif any(get_overlap_fraction(region, block.bounding_box) > 0 for region in regions):
+            block.text = mask(block.text)
+
+        return block
+
+    return PageTextExtractionOutput(
+        text=redacted_text,
+        words=words,
+        lines=[redact_container(line) for line in page_text.lines],
+        blocks=[redact_container(block) for block in page_text.blocks],
+    )
+
+
+def burn_in_page(
+    page: "DocumentPage",
+    regions: List[NormBBox],
+    *,
+    dpi: int = DEFAULT_REDACTION_DPI,
+    fill: Union[str, Tuple[int, int, int]] = "black",
+) -> Image.Image:
+    """
+    Rasterizes a page and fills the given regions
+    """
+    image = page.get_image(dpi=dpi, device="png16m").convert("RGB")
+    width, height = image.size
+
+    draw = ImageDraw.Draw(image)
+
+    for bbox in regions:
+        draw.rectangle(
+            (bbox.x0 * width, bbox.top * height, bbox.x1 * width, bbox.bottom * height),
+            fill=fill,
+        )
+
+    return image
+
+
+def replace_pages_with_images(document: "Document", page_images: Dict[int, Image.Image], *, dpi: int) -> bytes:
+    """
+    Returns the bytes of a copy of the document where each page in `page_images` (1-indexed)
+    is replaced by an image-only page of the same dimensions. Other pages are copied as-is.
+    """
+    reader = PdfReader(BytesIO(document.file_bytes))
+    writer = PdfWriter()
+
+    for page in document.pages:
+        if page.page_number not in page_images:
+            writer.add_page(reader.pages[page.page_number - 1])
+            continue
+
+        image_pdf = PdfReader(BytesIO(images_to_pdf_bytes([page_images[page.page_number]], dpi=dpi)))
+        image_page = image_pdf.pages[0]
+        image_page.scale_to(page.width, page.height)
+
+        writer.add_page(image_page)
+
+    output_stream = BytesIO()
+    writer.write(output_stream)
+
+    return output_stream.getvalue()
+
+
+def redact_document(
+    document: "Document",
+    regions: Optional[Dict[int, List[RedactionRegion]]] = None,
+    *,
+    patterns: Optional[List[Union[str, re.Pattern]]] = None,
+    provider_name: Optional[str] = None,
+    padding: float = 0.0,
+    dpi: int = DEFAULT_REDACTION_DPI,
+    fill: Union[str, Tuple[int, int, int]] = "black",
+) -> "Document":
+    """
+    Returns a new document with the given regions blacked out.
+
+    Regions can be given explicitly per page (1-indexed) as text blocks or bounding boxes,
+    and/or found by matching `patterns` against the text sidecar of `provider_name` (the
+    first provider by default).
+
+    Every page with at least one redaction is replaced by a raster of the page with the
+    regions burned in. None of that page's text objects, vector content or annotations
+    survive, so the redacted content cannot be copied out. Other pages are left untouched.
+    The document's text sidecars are carried along with the redacted words masked out.
+    """
+    page_regions: Dict[int, List[NormBBox]] = {}
+
+    for page_number, page_region_list in (regions or {}).items():
+        document.get_page(page_number)  # Validates the page number
+
+        page_regions.setdefault(page_number, []).extend(
+            region_to_bbox(region, padding=padding) for region in page_region_list
+        )
+
+    if patterns:
+        for page in document.pages:
+            page_text = page.get_text_data(provider_name)
+
+            if page_text is None:
+                continue
+
+            for word in find_pattern_matches(page_text, patterns):
+                page_regions.setdefault(page.page_number, []).append(region_to_bbox(word, padding=padding))
+
+    page_regions = {page_number: bboxes for page_number, bboxes in page_regions.items() if bboxes}
+
+    page_images = {
+        page_number: burn_in_page(document.get_page(page_number), bboxes, dpi=dpi, fill=fill)
+        for page_number, bboxes in page_regions.items()
+    }
+
+    file_bytes = replace_pages_with_images(document, page_images, dpi=dpi)
+
+    text_sidecars = {}
+
+    for sidecar_provider_name, sidecars in document.text_sidecars.items():
+        text_sidecars[sidecar_provider_name] = {
+            page_number: (
+                redact_page_text(page_text, page_regions[page_number])
+                if page_number in page_regions
+                else page_text.model_copy(deep=True)
+            )
+            for page_number, page_text in sidecars.items()
+        }
+
+    return document.__class__(
+        name=document.name,
+        file_bytes=file_bytes,
+        source_mime_type=document.source_mime_type,
+        text_sidecars=text_sidecars,
+    )
@@ -0,0 +1,496 @@
+import random
+import re
+from datetime import date, datetime, timedelta
+from math import atan2, ceil, degrees, hypot
+from typing import TYPE_CHECKING, Dict, List, Literal, Optional, Sequence, Tuple
+
+from PIL import Image, ImageDraw, ImageFont
+
+from docprompt.masking.redaction import get_overlap_fraction, replace_pages_with_images
+from docprompt.schema.layout import TextBlock
+from docprompt.schema.operations import PageTextExtractionOutput
+from docprompt.textual.date_extraction import get_date_strings_for_text
+
+if TYPE_CHECKING:
+    from docprompt.schema.document import Document, DocumentPage
+
+ReplacementCategory = Literal["name", "number", "date", "text"]
+
+DEFAULT_SYNTHESIS_DPI = 200
+
+# fmt: off
+FIRST_NAMES = [
+    "Ada", "Alan", "Amir", "Anna", "Ben", "Carla", "Chen", "Dana", "David", "Elena", "Emma", "Felix", "Grace",
+    "Hana", "Ivan", "James", "Jose", "Kai", "Laura", "Leo", "Maria", "Mei", "Nina", "Omar", "Paul", "Priya",
+    "Rosa", "Sam", "Sofia", "Tom", "Uma", "Victor", "Wei", "Yara", "Zoe", "Michael", "Jennifer", "Patricia",
+]
+
+LAST_NAMES = [
+    "Abe", "Adams", "Baker", "Brown", "Chen", "Clark", "Cruz", "Diaz", "Evans", "Fischer", "Garcia", "Gray",
+    "Hall", "Ito", "Jones", "Kim", "Lee", "Lopez", "Martin", "Moore", "Nguyen", "Novak", "Okafor", "Park",
+    "Patel", "Reyes", "Rossi", "Silva", "Smith", "Tanaka", "Turner", "Walker", "Wong", "Young", "Anderson",
+    "Henderson", "Richardson", "Williamson",
+]
+# fmt: on
+
+DATE_FORMATS = [
+    "%m/%d/%Y",
+    "%m/%d/%y",
+    "%Y/%m/%d",
+    "%m-%d-%Y",
+    "%m-%d-%y",
+    "%Y-%m-%d",
+    "%Y.%m.%d",
+    "%d.%m.%Y",
+    "%d.%m.%y",
+    "%B %d, %Y",
+    "%b %d, %Y",
+    "%B %d %Y",
+    "%b %d %Y",
+    "%d %B %Y",
+    "%d %b %Y",
+    "%Y %B %d",
+    "%Y %b %d",
+]
+
+
+class ReplacementPolicy:
+    """
+    Decides which kind of synthetic text replaces a piece of sensitive text, and generates it.
+
+    Text is categorized as a date, number or name, in that order of precedence, if the
+    category is enabled. Anything else is replaced by random letters and digits with the
+    same shape. Replacements keep the original token count and roughly the original length,
+    and the same original text always maps to the same replacement.
+    """
+
+    def __init__(
+        self,
+        categories: Sequence[ReplacementCategory] = ("name", "number", "date"),
+        *,
+        seed: Optional[int] = None,
+        first_names: Optional[List[str]] = None,
+        last_names: Optional[List[str]] = None,
+    ):
+        self.categories = set(categories)
+        self.random = random.Random(seed)
+        self.first_names = first_names or FIRST_NAMES
+        self.last_names = last_names or LAST_NAMES
+
+        self._replacements: Dict[str, str] = {}
+
+    def categorize(self, text: str) -> ReplacementCategory:
+        stripped = text.strip()
+
+        if "date" in self.categories and (get_date_strings_for_text(stripped) or _match_date_format(stripped)):
+            return "date"
+
+        alnum = [c for c in stripped if c.isalnum()]
+
+        if "number" in self.categories and alnum and sum(c.isdigit() for c in alnum) / len(alnum) >= 0.5:
+            return "number"
+
+        if "name" in self.categories and alnum and all(
+            token[:1].isupper() and token.replace("-", "").replace("'", "").rstrip(".,").isalpha()
+            for token in stripped.split()
+        ):
+            return "name"
+
+        return "text"
+
+    def replace(self, text: str) -> str:
+        if text not in self._replacements:
+            self._replacements[text] = self._generate(text, self.categorize(text))
+
+        return self._replacements[text]
+
+    def _generate(self, text: str, category: ReplacementCategory) -> str:
+        if category == "date":
+            return self._fake_date(text)
+
+        if category == "name":
+            tokens = re.split(r"(\s+)", text)
+            word_count = len([token for token in tokens if token and not token.isspace()])
+            fake_tokens = []
+            word_index = 0
+
+            for token in tokens:
+                if not token or token.isspace():
+                    fake_tokens.append(token)
+                    continue
+
+                # The last token of a multi-word name is treated as the surname
+                is_surname = word_index > 0 and word_index == word_count - 1
+                names = self.last_names if is_surname else self.first_names
+                fake_tokens.append(self._fake_name_token(token, names))
+                word_index += 1
+
+            return "".join(fake_tokens)
+
+        return self._fake_shape(text)
+
+    def _fake_name_token(self, token: str, names: List[str]) -> str:
+        core = token.rstrip(".,;:")
+        suffix = token[len(core) :]
+
+        if len(core) <= 2:
+            # Initials
+            return self.random.choice("ABCDEFGHJKLMNPRSTW") + core[1:] + suffix
+
+        closest = min(abs(len(name) - len(core)) for name in names)
+        candidates = [name for name in names if abs(len(name) - len(core)) == closest and name != core]
+        name = self.random.choice(candidates or names)
+
+        if core.isupper():
+            name = name.upper()
+
+        return name + suffix
+
+    def _fake_date(self, text: str) -> str:
+        fake = date(1970, 1, 1) + timedelta(days=self.random.randrange(365 * 55))
+        date_format = _match_date_format(text.strip())
+
+        if date_format is None:
+            return self._fake_shape(text)
+
+        leading = text[: len(text) - len(text.lstrip())]
+        trailing = text[len(text.rstrip()) :]
+
+        return leading + fake.strftime(date_format) + trailing
+
+    def _fake_shape(self, text: str) -> str:
+        chars = []
+
+        for c in text:
+            if c.isdigit():
+                chars.append(self.random.choice("0123456789"))
+            elif c.isalpha() and c.isupper():
+                chars.append(self.random.choice("ABCDEFGHIJKLMNOPQRSTUVWXYZ"))
+            elif c.isalpha():
+                chars.append(self.random.choice("abcdefghijklmnopqrstuvwxyz"))
+            else:
+                chars.append(c)
+
+        return "".join(chars)
+
+
+def _match_date_format(text: str) -> Optional[str]:
+    for date_format in DATE_FORMATS:
+        try:
+            datetime.strptime(text, date_format)
+            return date_format
+        except ValueError:
+            continue
+
+    return None
+
+
+def _load_font(size: int, font_path: Optional[str] = None) -> ImageFont.ImageFont:
+    if font_path is not None:
+        return ImageFont.truetype(font_path, size)
+
+    try:
+        return ImageFont.truetype("DejaVuSans.ttf", size)
+    except OSError:
+        pass
+
+    try:
+        return ImageFont.load_default(size=size)  # Pillow >= 10.1
+    except TypeError:
+        return ImageFont.load_default()
+
+
+def fit_font(text: str, width: float, height: float, font_path: Optional[str] = None) -> ImageFont.ImageFont:
+    """
+    Returns the largest font at which `text` fits within a box of the given pixel size
+    """
+    draw = ImageDraw.Draw(Image.new("L", (1, 1)))
+
+    low, high = 1, max(int(height * 1.5), 2)
+    best = _load_font(low, font_path)
+
+    while low <= high:
+        size = (low + high) // 2
+        font = _load_font(size, font_path)
+        left, top, right, bottom = draw.textbbox((0, 0), text, font=font)
+
+        if right - left <= width and bottom - top <= height:
+            best, low = font, size + 1
+        else:
+            high = size - 1
+
+    return best
+
+
+def _estimate_colors(image: Image.Image, vertices: List[Tuple[float, float]]) -> Tuple[tuple, tuple]:
+    """
+    Estimates the background and text colors of a region, using the region's border for
+    the background and the pixels least like the background for the text
+    """
+    x0 = max(int(min(x for x, _ in vertices)) - 2, 0)
+    y0 = max(int(min(y for _, y in vertices)) - 2, 0)
+    x1 = min(ceil(max(x for x, _ in vertices)) + 2, image.width)
+    y1 = min(ceil(max(y for _, y in vertices)) + 2, image.height)
+
+    crop = image.crop((x0, y0, max(x1, x0 + 1), max(y1, y0 + 1)))
+    width, height = crop.size
+    pixels = list(crop.getdata())
+
+    border = [pixels[column] for column in range(width)]
+    border += [pixels[(height - 1) * width + column] for column in range(width)]
+    border += [pixels[row * width] for row in range(height)]
+    border += [pixels[row * width + width - 1] for row in range(height)]
+
+    background = tuple(sorted(channel)[len(channel) // 2] for channel in zip(*border))
+
+    def distance(pixel):
+        return sum((a - b) ** 2 for a, b in zip(pixel, background))
+
+    farthest = sorted(pixels, key=distance, reverse=True)[: max(len(pixels) // 10, 1)]
+    foreground = tuple(sum(channel) // len(channel) for channel in zip(*farthest))
+
+    if distance(foreground) < 48**2:
+        # Nothing stands out from the background; fall back to a contrasting color
+        foreground = (0, 0, 0) if sum(background) > 382 else (255, 255, 255)
+
+    return background, foreground
+
+
+def _block_vertices(block: TextBlock, width: int, height: int) -> List[Tuple[float, float]]:
+    if block.geometry.bounding_poly is not None and len(block.geometry.bounding_poly.normalized_vertices) == 4:
+        return [(v.x * width, v.y * height) for v in block.geometry.bounding_poly.normalized_vertices]
+
+    bbox = block.bounding_box
+
+    return [
+        (bbox.x0 * width, bbox.top * height),
+        (bbox.x1 * width, bbox.top * height),
+        (bbox.x1 * width, bbox.bottom * height),
+        (bbox.x0 * width, bbox.bottom * height),
+    ]
+
+
+def render_synthetic_text(
+    image: Image.Image, block: TextBlock, text: str, *, font_path: Optional[str] = None
+) -> Image.Image:
+    """
+    Paints over a text block with its background color and renders `text` in its place,
+    fitted to the block's size and rotated to match the skew of its bounding poly
+    """
+    vertices = _block_vertices(block, image.width, image.height)
+    top_left, top_right, _, bottom_left = vertices
+
+    background, foreground = _estimate_colors(image, vertices)
+
+    draw = ImageDraw.Draw(image)
+    draw.polygon(vertices, fill=background, outline=background, width=2)
+
+    # Measure in pixel space, since normalized coordinates distort angles on non-square pages
+    box_width = hypot(top_right[0] - top_left[0], top_right[1] - top_left[1])
+    box_height = hypot(bottom_left[0] - top_left[0], bottom_left[1] - top_left[1])
+    skew_angle = degrees(atan2(top_left[1] - top_right[1], top_right[0] - top_left[0]))
+
+    if box_width < 1 or box_height < 1 or not text.strip():
+        return image
+
+    font = fit_font(text, box_width, box_height, font_path)
+
+    canvas = Image.new("RGBA", (ceil(box_width), ceil(box_height)), (0, 0, 0, 0))
+    canvas_draw = ImageDraw.Draw(canvas)
+    left, top, _, bottom = canvas_draw.textbbox((0, 0), text, font=font)
+    canvas_draw.text((-left, (canvas.height - (bottom - top)) / 2 - top), text, font=font, fill=foreground)
+
+    rotated = canvas.rotate(skew_angle, expand=True, resample=Image.BICUBIC)
+
+    center_x = sum(x for x, _ in vertices) / 4
+    center_y = sum(y for _, y in vertices) / 4
+
+    image.paste(rotated, (round(center_x - rotated.width / 2), round(center_y - rotated.height / 2)), rotated)
+
+    return image
+
+
+def _get_removal_range(text: str, start: int, end: int) -> Tuple[int, int]:
+    """
+    Extends a removed word's span over the spaces after it, or before it at the end of a line,
+    so that removing it doesn't leave a double space behind
+    """
+    extended_end = end
+
+    while extended_end < len(text) and text[extended_end] in " \t":
+        extended_end += 1
+
+    if extended_end > end:
+        return start, extended_end
+
+    while start > 0 and text[start - 1] in " \t":
+        start -= 1
+
+    return start, end
+
+
+def _shift_index(index: int, edits: List[Tuple[int, int, int]]) -> int:
+    return index + sum(delta for _, end, delta in edits if end <= index)
+
+
+def synthesize_page_text(
+    page_text: PageTextExtractionOutput, replacements: List[Tuple[TextBlock, str]], threshold: float = 0.5
+) -> PageTextExtractionOutput:
+    """
+    Returns a copy of a page's text extraction output describing the synthetic text.
+
+    Words mostly covered by a replaced block take the corresponding token of the replacement.
+    If the token counts differ, the first covered word takes the whole replacement and the
+    others are removed. The page text is rewritten and every text span is shifted to account
+    for length changes.
+    """
+    words = [word.model_copy(deep=True) for word in page_text.words]
+    originals = {id(word): word.text for word in words}
+    removed = set()
+
+    for block, fake_text in replacements:
+        covered = [word for word in words if get_overlap_fraction(word.bounding_box, block.bounding_box) >= threshold]
+        covered.sort(key=lambda word: (word.bounding_box.top, word.bounding_box.x0))
+
+        fake_tokens = fake_text.split()
+
+        if len(covered) == len(fake_tokens):
+            for word, token in zip(covered, fake_tokens):
+                word.text = token
+        elif covered:
+            covered[0].text = fake_text.strip()
+            removed.update(id(word) for word in covered[1:])
+
+    text = page_text.text
+    edits: List[Tuple[int, int, str]] = []
+
+    for word in words:
+        if not word.text_spans:
+            continue
+
+        span = word.text_spans[0]
+
+        if id(word) in removed:
+            edits.append((*_get_removal_range(text, span.start_index, span.end_index), ""))
+        elif word.text != originals[id(word)]:
+            edits.append((span.start_index, span.end_index, word.text))
+
+    edits.sort()
+
+    # A removed word can take the whitespace between it and a neighbouring edit, so clip any overlap
+    for i in range(1, len(edits)):
+        previous_end = edits[i - 1][1]
+
+        if edits[i][0] < previous_end:
+            edits[i] = (previous_end, max(previous_end, edits[i][1]), edits[i][2])
+
+    for start, end, new_text in reversed(edits):
+        text = text[:start] + new_text + text[end:]
+
+    removed_words = [word for word in words if id(word) in removed]
+    shifts = [(start, end, len(new_text) - (end - start)) for start, end, new_text in edits]
+
+    words = [word for word in words if id(word) not in removed]
+
+    for word in words:
+        for span in word.text_spans or []:
+            span.start_index = _shift_index(span.start_index, shifts)
+            span.end_index = _shift_index(span.end_index, shifts)
+
+    def rebuild(block: TextBlock) -> TextBlock:
+        block = block.model_copy(deep=True)
+
+        if block.text_spans:
+            for span in block.text_spans:
+                span.start_index = _shift_index(span.start_index, shifts)
+                span.end_index = _shift_index(span.end_index, shifts)
+
+            block.text = "".join(text[span.start_index : span.end_index] for span in block.text_spans)
+        else:
+            for word in words:
+                if word.text != originals[id(word)] and originals[id(word)]:
+                    block.text = block.text.replace(originals[id(word)], word.text, 1)
+
+            for word in removed_words:
+                block.text = re.sub(r"  +", " ", block.text.replace(word.text, "", 1)).strip()
+
+        return block
+
+    return PageTextExtractionOutput(
+        text=text,
+        words=words,
+        lines=[rebuild(line) for line in page_text.lines],
+        blocks=[rebuild(block) for block in page_text.blocks],
+    )
+
+
+def synthesize_page(
+    page: "DocumentPage",
+    replacements: List[Tuple[TextBlock, str]],
+    *,
+    dpi: int = DEFAULT_SYNTHESIS_DPI,
+    font_path: Optional[str] = None,
+) -> Image.Image:
+    """
+    Rasterizes a page and replaces each block's text with its synthetic text
+    """
+    image = page.get_image(dpi=dpi, device="png16m").convert("RGB")
+
+    for block, fake_text in replacements:
+        image = render_synthetic_text(image, block, fake_text, font_path=font_path)
+
+    return image
+
+
+def synthesize_document(
+    document: "Document",
+    blocks: Dict[int, List[TextBlock]],
+    policy: Optional[ReplacementPolicy] = None,
+    *,
+    dpi: int = DEFAULT_SYNTHESIS_DPI,
+    font_path: Optional[str] = None,
+) -> "Document":
+    """
+    Returns a new document where each of the given text blocks (per page, 1-indexed) is
+    painted over and replaced by realistic synthetic text chosen by `policy`.
+
+    As with redaction, every affected page is replaced by a raster of the page, so none of
+    the original text objects survive. The text sidecars are updated to describe the
+    synthetic text instead of the original.
+    """
+    policy = policy or ReplacementPolicy()
+
+    page_replacements: Dict[int, List[Tuple[TextBlock, str]]] = {}
+
+    for page_number, page_blocks in blocks.items():
+        document.get_page(page_number)  # Validates the page number
+
+        page_replacements[page_number] = [(block, policy.replace(block.text)) for block in page_blocks]
+
+    page_replacements = {page_number: items for page_number, items in page_replacements.items() if items}
+
+    page_images = {
+        page_number: synthesize_page(document.get_page(page_number), items, dpi=dpi, font_path=font_path)
+        for page_number, items in page_replacements.items()
+    }
+
+    file_bytes = replace_pages_with_images(document, page_images, dpi=dpi)
+
+    text_sidecars = {}
+
+    for provider_name, sidecars in document.text_sidecars.items():
+        text_sidecars[provider_name] = {
+            page_number: (
+                synthesize_page_text(page_text, page_replacements[page_number])
+                if page_number in page_replacements
+                else page_text.model_copy(deep=True)
+            )
+            for page_number, page_text in sidecars.items()
+        }
+
+    return document.__class__(
+        name=document.name,
+        file_bytes=file_bytes,
+        source_mime_type=document.source_mime_type,
+        text_sidecars=text_sidecars,
+    )
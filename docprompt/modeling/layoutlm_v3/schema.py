@@ -1,13 +1,14 @@
-from io import BytesIO
 from typing import List, Optional, Tuple
 
 from PIL import Image, ImageDraw
 from pydantic import BaseModel, Field
 
-from docprompt._exec.ghostscript import rasterize_page_to_bytes
-from docprompt.schema.document import Document
+from docprompt.schema.document import Document, DocumentPage
 from docprompt.schema.layout import NormBBox, TextBlock
 
+# The resolution page images are rendered at when none is given
+LAYOUTLMV3_IMAGE_DPI = 200
+
 
 class LayoutLMV3BaseInput(BaseModel):
     image: Image.Image
@@ -69,34 +70,31 @@ def merge_adjacent_tokens_and_bboxes(tokens: list[str], bboxes: list[Tuple[int,
     return tokens, bboxes
 
 
-def layoutlmv3_inputs_from_document_page(
-    document: Document,
-    page_number: int,
+def layoutlmv3_inputs_from_page(
+    page: DocumentPage,
     image: Optional[Image.Image] = None,
     merge_adjacent_tokens: bool = True,
 ):
     """
-    Returns a list of LayoutLMv3 input objects for a given document
+    Returns a LayoutLMv3 input object for a given document page
     """
 
-    if page_number > document.num_pages:
-        raise ValueError(f"Page number {page_number} is out of range for document {document}")
+    text_data = page.text_data
 
-    if document.text_data is None:
-        raise ValueError(f"Document {document} does not have text data. Try running `perform_text_extraction` first")
-
-    text_data = document.text_data
+    if text_data is None:
+        raise ValueError(
+            f"Page {page.page_number} of {page.document} does not have text data. "
+            "Try running `perform_text_extraction` first"
+        )
 
     if image is None:
-        image_bytes = rasterize_page_to_bytes(document.file_path, page_number)
-
-        image = Image.open(BytesIO(image_bytes))
+        image = page.get_image(dpi=LAYOUTLMV3_IMAGE_DPI)
 
     image = image.convert("RGB")  # LayoutLMV3 needs 3 channels
 
     assert image.size[0] > 0 and image.size[1] > 0, "Image must have non-zero dimensions"
 
-    word_blocks = text_data[page_number].words
+    word_blocks = text_data.words
 
     tokens = []
     bboxes = []
@@ -122,3 +120,21 @@ def layoutlmv3_inputs_from_document_page(
     assert len(tokens) == len(bboxes), "Tokens and bboxes must be the same length"
 
     return LayoutLMV3BaseInput(image=image, tokens=tokens, bboxes=bboxes)
+
+
+def layoutlmv3_inputs_from_document_page(
+    document: Document,
+    page_number: int,
+    image: Optional[Image.Image] = None,
+    merge_adjacent_tokens: bool = True,
+):
+    """
+    Returns a LayoutLMv3 input object for a given page of a document
+    """
+
+    if page_number > document.num_pages:
+        raise ValueError(f"Page number {page_number} is out of range for document {document}")
+
+    return layoutlmv3_inputs_from_page(
+        document.get_page(page_number), image=image, merge_adjacent_tokens=merge_adjacent_tokens
+    )
@@ -0,0 +1,81 @@
+import os
+from threading import Lock
+from typing import Dict, Optional, Type, Union
+
+from .base import BaseRasterizer, Raster, RasterDevice
+from .cache import RasterCache, get_raster_cache, set_raster_cache
+from .ghostscript import GhostscriptRasterizer
+from .pdfium import PdfiumRasterizer
+from .pdftoppm import PdftoppmRasterizer
+
+RASTERIZERS: Dict[str, Type[BaseRasterizer]] = {
+    GhostscriptRasterizer.name: GhostscriptRasterizer,
+    PdftoppmRasterizer.name: PdftoppmRasterizer,
+    PdfiumRasterizer.name: PdfiumRasterizer,
+}
+
+_default_rasterizer: Optional[BaseRasterizer] = None
+
+# Rasterizers requested by name are shared, since some of them hold worker pools
+_instances: Dict[str, BaseRasterizer] = {}
+_instances_lock = Lock()
+
+
+def _resolve(rasterizer: Union[str, BaseRasterizer]) -> BaseRasterizer:
+    if isinstance(rasterizer, BaseRasterizer):
+        return rasterizer
+
+    if rasterizer not in RASTERIZERS:
+        raise ValueError(f"Unknown rasterizer {rasterizer}, must be one of {list(RASTERIZERS)}")
+
+    with _instances_lock:
+        if rasterizer not in _instances:
+            _instances[rasterizer] = RASTERIZERS[rasterizer]()
+
+        return _instances[rasterizer]
+
+
+def set_default_rasterizer(rasterizer: Union[str, BaseRasterizer]) -> None:
+    """
+    Sets the rasterizer used when none is passed to a rasterize call
+    """
+    global _default_rasterizer
+
+    _default_rasterizer = _resolve(rasterizer)
+
+
+def get_default_rasterizer() -> BaseRasterizer:
+    """
+    Returns the default rasterizer, which is set with `set_default_rasterizer` or the
+    DOCPROMPT_RASTERIZER environment variable and falls back to Ghostscript
+    """
+    global _default_rasterizer
+
+    if _default_rasterizer is None:
+        _default_rasterizer = _resolve(os.getenv("DOCPROMPT_RASTERIZER", GhostscriptRasterizer.name))
+
+    return _default_rasterizer
+
+
+def get_rasterizer(rasterizer: Optional[Union[str, BaseRasterizer]] = None) -> BaseRasterizer:
+    if rasterizer is None:
+        return get_default_rasterizer()
+
+    return _resolve(rasterizer)
+
+
+__all__ = [
+    "BaseRasterizer",
+    "GhostscriptRasterizer",
+    "PdfiumRasterizer",
+    "PdftoppmRasterizer",
+    "RASTERIZERS",
+    "Raster",
+    "RasterCache",
+    "RasterDevice",
+    "get_default_rasterizer",
+    "get_raster_cache",
+    "get_rasterizer",
+    "set_default_rasterizer",
+    "set_raster_cache",
+]
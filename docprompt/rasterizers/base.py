@@ -0,0 +1,273 @@
+from abc import ABCMeta, abstractmethod
+from collections import defaultdict
+from io import BytesIO
+from os import PathLike
+from typing import Dict, Iterable, List, Literal, Optional, Union
+
+from PIL import Image
+from pydantic import BaseModel, Field
+
+from docprompt._exec.ghostscript import DEVICE_FORMATS
+
+RasterDevice = Literal[
+    "pnggray",
+    "png16m",
+    "png256",
+    "pngmono",
+    "jpeg",
+    "jpeggray",
+    "tiffg4",
+    "tifflzw",
+    "tiffgray",
+    "tiff24nc",
+    "webp",
+]
+
+SUPPORTED_DEVICES = list(DEVICE_FORMATS)
+
+# Devices whose output has no color, which backends can render in grayscale to save work
+GRAYSCALE_DEVICES = ["pnggray", "pngmono", "jpeggray", "tiffg4", "tifflzw", "tiffgray"]
+
+
+def validate_device(device: str) -> RasterDevice:
+    if device not in SUPPORTED_DEVICES:
+        raise ValueError(f"Invalid device {device}, must be one of {SUPPORTED_DEVICES}")
+
+    return device
+
+
+def get_device_format(device: RasterDevice) -> str:
+    """
+    Returns the image format a device produces, which is one of png, jpeg, tiff or webp
+    """
+    return DEVICE_FORMATS[validate_device(device)]
+
+
+def _to_bilevel(image: Image.Image) -> Image.Image:
+    # A plain threshold, since OCR reads thresholded text better than dithered text
+    return image.convert("L").point(lambda value: 255 if value >= 128 else 0, mode="1")
+
+
+def encode_image(image: Image.Image, device: RasterDevice, quality: Optional[int] = None) -> bytes:
+    """
+    Encodes an image with the format and pixel format Ghostscript uses for each device, so that
+    every rasterizer produces the same kind of output
+    """
+    save_kwargs = {}
+
+    if device in ["pnggray", "jpeggray", "tiffgray"]:
+        image = image.convert("L")
+    elif device in ["pngmono", "tiffg4", "tifflzw"]:
+        image = _to_bilevel(image)
+    elif device == "png256":
+        image = image.convert("RGB").quantize(256)
+    else:
+        image = image.convert("RGB")
+
+    image_format = get_device_format(device)
+
+    if image_format in ["jpeg", "webp"] and quality is not None:
+        save_kwargs["quality"] = quality
+
+    if image_format == "tiff":
+        save_kwargs["compression"] = "group4" if device == "tiffg4" else "tiff_lzw"
+
+    output = BytesIO()
+    image.save(output, format=image_format.upper(), **save_kwargs)
+
+    return output.getvalue()
+
+
+def downscale_image(image: Image.Image, downscale_factor: Optional[int]) -> Image.Image:
+    """
+    Emulates Ghostscript's DownScaleFactor, which renders at the requested DPI and then
+    shrinks the result by an integer factor to anti-alias it
+    """
+    if not downscale_factor or downscale_factor <= 1:
+        return image
+
+    size = (max(1, image.width // downscale_factor), max(1, image.height // downscale_factor))
+
+    return image.resize(size, Image.BOX)
+
+
+class Raster(BaseModel):
+    """
+    A rasterized page, along with the DPI it was rendered at
+    """
+
+    page_number: int
+    dpi: int
+    width: int
+    height: int
+    data: bytes = Field(repr=False)
+
+    @classmethod
+    def from_bytes(cls, page_number: int, dpi: int, data: bytes) -> "Raster":
+        width, height = Image.open(BytesIO(data)).size  # Only reads the header
+
+        return cls(page_number=page_number, dpi=dpi, width=width, height=height, data=data)
+
+    @property
+    def image(self) -> Image.Image:
+        return Image.open(BytesIO(self.data))
+
+
+class BaseRasterizer(metaclass=ABCMeta):
+    """
+    Renders the pages of a PDF to image bytes in the format of the chosen device (PNG, JPEG,
+    TIFF or WebP). Implementations must produce the same format and pixel format for each
+    device, and render the crop box of each page with its rotation applied.
+    """
+
+    name: str
+
+    @abstractmethod
+    def _rasterize(
+        self,
+        fp: Union[PathLike, str],
+        pages: Optional[List[int]],
+        *,
+        dpi: int,
+        device: RasterDevice,
+        downscale_factor: Optional[int],
+        quality: Optional[int],
+    ) -> Dict[int, bytes]:
+        """
+        Rasterizes the given pages (1-indexed), or every page if `pages` is None. `quality`
+        only applies to the jpeg and webp devices.
+        """
+        raise NotImplementedError
+
+    def is_available(self) -> bool:
+        """
+        Whether the rasterizer's binary or package is installed
+        """
+        return True
+
+    def rasterize_page(
+        self,
+        fp: Union[PathLike, str],
+        page_number: int,
+        *,
+        dpi: int = 100,
+        device: RasterDevice = "png16m",
+        downscale_factor: Optional[int] = None,
+        quality: Optional[int] = None,
+    ) -> bytes:
+        rasters = self._rasterize(
+            fp,
+            [page_number],
+            dpi=dpi,
+            device=validate_device(device),
+            downscale_factor=downscale_factor,
+            quality=quality,
+        )
+
+        if page_number not in rasters:
+            raise ValueError(f"{self.name} did not render page {page_number}")
+
+        return rasters[page_number]
+
+    def rasterize_pdf(
+        self,
+        fp: Union[PathLike, str],
+        *,
+        dpi: int = 100,
+        device: RasterDevice = "pnggray",
+        downscale_factor: Optional[int] = None,
+        quality: Optional[int] = None,
+        pages: Optional[Iterable[int]] = None,
+    ) -> Dict[int, bytes]:
+        """
+        Rasterizes every page, or only the given page numbers, keyed by page number
+        """
+        return self._rasterize(
+            fp,
+            sorted(set(pages)) if pages is not None else None,
+            dpi=dpi,
+            device=validate_device(device),
+            downscale_factor=downscale_factor,
+            quality=quality,
+        )
+
+    def rasterize_tiff(
+        self,
+        fp: Union[PathLike, str],
+        *,
+        dpi: int = 100,
+        device: RasterDevice = "tiffg4",
+        downscale_factor: Optional[int] = None,
+    ) -> bytes:
+        """
+        Rasterizes the document to a single multi-page TIFF
+        """
+        if get_device_format(device) != "tiff":
+            raise ValueError("Device must be a TIFF device for rasterize_tiff")
+
+        rasters = self.rasterize_pdf(fp, dpi=dpi, device=device, downscale_factor=downscale_factor)
+        images = [Image.open(BytesIO(raster)) for raster in rasters.values()]
+
+        output = BytesIO()
+        images[0].save(
+            output,
+            format="TIFF",
+            save_all=True,
+            append_images=images[1:],
+            compression="group4" if device == "tiffg4" else "tiff_lzw",
+        )
+
+        return output.getvalue()
+
+    def rasterize_to_size(
+        self,
+        fp: Union[PathLike, str],
+        pages: Optional[Iterable[int]] = None,
+        *,
+        max_width: Optional[int] = None,
+        max_height: Optional[int] = None,
+        max_pixels: Optional[int] = None,
+        device: RasterDevice = "png16m",
+        downscale_factor: Optional[int] = None,
+        quality: Optional[int] = None,
+    ) -> Dict[int, Raster]:
+        """
+        Rasterizes pages (or every page, if `pages` is None) at the highest DPI that fits each
+        within the given limits. Pages in a PDF can differ in size, so the DPI is picked per page.
+        """
+        from pypdf import PdfReader
+
+        from docprompt.schema.page_geometry import PageGeometry
+
+        reader = PdfReader(fp)
+
+        if pages is None:
+            pages = list(range(1, len(reader.pages) + 1))
+
+        pages_by_dpi: Dict[int, List[int]] = defaultdict(list)
+
+        for page_number in pages:
+            if page_number < 1 or page_number > len(reader.pages):
+                raise ValueError(f"Page number must be between 1 and {len(reader.pages)}")
+
+            geometry = PageGeometry.from_pdf_page(reader.pages[page_number - 1])
+            dpi = geometry.get_dpi_for_size(max_width=max_width, max_height=max_height, max_pixels=max_pixels)
+            pages_by_dpi[dpi].append(page_number)
+
+        rasters = {}
+
+        for dpi, page_numbers in pages_by_dpi.items():
+            # A downscale factor shrinks the output by that factor, so render at a multiple of the DPI to land on it
+            outputs = self._rasterize(
+                fp,
+                page_numbers,
+                dpi=dpi * downscale_factor if downscale_factor else dpi,
+                device=validate_device(device),
+                downscale_factor=downscale_factor,
+                quality=quality,
+            )
+
+            for page_number, data in outputs.items():
+                rasters[page_number] = Raster.from_bytes(page_number, dpi, data)
+
+        return dict(sorted(rasters.items()))
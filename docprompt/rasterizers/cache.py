@@ -0,0 +1,306 @@
+import os
+import time
+import uuid
+from datetime import datetime
+from os import PathLike
+from typing import Dict, List, Optional, Union
+
+import fsspec
+from fsspec.implementations.local import LocalFileSystem
+
+from .base import BaseRasterizer, RasterDevice, get_device_format
+
+DEFAULT_MAX_SIZE = 2 * 1024**3  # 2 GB
+
+TEMP_SUFFIX = ".tmp"
+
+# When full, the cache is trimmed to this fraction of its maximum size, so that it isn't listed
+# again for every new raster
+EVICTION_TARGET = 0.9
+
+# Temporary files older than this are assumed to be left behind by a crashed process
+STALE_TEMP_AGE = 60 * 60
+
+
+def _get_modified(info: dict) -> float:
+    """
+    Returns the modification time of a file from fsspec's file info, whose key and type
+    depend on the filesystem
+    """
+    for key in ["mtime", "LastModified", "updated", "last_modified", "modified"]:
+        value = info.get(key)
+
+        if isinstance(value, (int, float)):
+            return float(value)
+
+        if isinstance(value, datetime):
+            return value.timestamp()
+
+        if isinstance(value, str):
+            try:
+                return datetime.fromisoformat(value.replace("Z", "+00:00")).timestamp()
+            except ValueError:
+                continue
+
+    return 0.0
+
+
+class RasterCache:
+    """
+    A persistent cache of page rasters on local disk or any filesystem supported by fsspec,
+    keyed by document hash, page, DPI, device and rasterizer.
+
+    Once the cache grows beyond `max_size` bytes, the least recently used rasters are evicted.
+    On local disk a cache hit marks the raster as used; on other filesystems updating the
+    modification time would mean rewriting it, so rasters are evicted oldest first instead.
+
+    Several processes can share a cache. Rasters are written to a temporary file and moved
+    into place, so readers never see a partial raster, and files deleted by another process
+    are treated as cache misses.
+    """
+
+    def __init__(
+        self,
+        path: Union[PathLike, str],
+        *,
+        max_size: Optional[int] = DEFAULT_MAX_SIZE,
+        storage_options: Optional[dict] = None,
+    ) -> None:
+        self.fs, self.root = fsspec.core.url_to_fs(str(path), **(storage_options or {}))
+        self.max_size = max_size
+
+        # The total size as of the last listing plus what this process has added since, so that
+        # the cache only needs to be listed again when it may have outgrown `max_size`
+        self._tracked_size: Optional[int] = None
+
+        self.fs.makedirs(self.root, exist_ok=True)
+
+    @property
+    def is_local(self) -> bool:
+        return isinstance(self.fs, LocalFileSystem)
+
+    def get_path(
+        self,
+        document_hash: str,
+        page_number: int,
+        *,
+        dpi: int,
+        device: RasterDevice,
+        rasterizer: str,
+        downscale_factor: Optional[int] = None,
+        quality: Optional[int] = None,
+    ) -> str:
+        name = f"{page_number}_{dpi}_{device}_{rasterizer}"
+
+        # Only added when set, since they don't apply to most rasters
+        if downscale_factor:
+            name += f"_d{downscale_factor}"
+
+        if quality is not None:
+            name += f"_q{quality}"
+
+        return f"{self.root}/{document_hash}/{name}.{get_device_format(device)}"
+
+    def get(self, document_hash: str, page_number: int, **key) -> Optional[bytes]:
+        """
+        Returns a cached raster, or None if it isn't cached. Takes the same keyword arguments
+        as `get_path`
+        """
+        path = self.get_path(document_hash, page_number, **key)
+
+        try:
+            data = self.fs.cat_file(path)
+        except FileNotFoundError:
+            return None
+
+        if self.is_local:
+            try:
+                os.utime(path)
+            except FileNotFoundError:  # Evicted by another process since we read it
+                pass
+
+        return data
+
+    def put(self, document_hash: str, page_number: int, data: bytes, **key) -> None:
+        """
+        Caches a raster. Doesn't evict anything, so call `evict_if_full` after adding a batch of rasters
+        """
+        path = self.get_path(document_hash, page_number, **key)
+
+        if self._tracked_size is not None:
+            self._tracked_size += len(data)
+
+        if not self.is_local:
+            # Object stores write each object atomically, so there's no need for a temporary file
+            self.fs.pipe_file(path, data)
+            return
+
+        os.makedirs(os.path.dirname(path), exist_ok=True)
+
+        temp_path = f"{path}.{os.getpid()}.{uuid.uuid4().hex}{TEMP_SUFFIX}"
+
+        with open(temp_path, "wb") as f:
+            f.write(data)
+
+        os.replace(temp_path, path)
+
+    def _list(self) -> List[dict]:
+        try:
+            return list(self.fs.find(self.root, detail=True).values())
+        except FileNotFoundError:
+            return []
+
+    def _remove(self, path: str) -> None:
+        try:
+            self.fs.rm_file(path)
+        except FileNotFoundError:  # Already removed by another process
+            pass
+
+    def size(self) -> int:
+        """
+        Returns the total size of the cached rasters in bytes
+        """
+        return sum(info["size"] for info in self._list() if not info["name"].endswith(TEMP_SUFFIX))
+
+    def evict(self, target_size: Optional[int] = None) -> None:
+        """
+        Removes the least recently used rasters until the cache fits within `target_size` bytes,
+        which defaults to `max_size`
+        """
+        if target_size is None:
+            target_size = self.max_size
+
+        if target_size is None:
+            return
+
+        entries = []
+        now = time.time()
+
+        for info in self._list():
+            if info["name"].endswith(TEMP_SUFFIX):
+                if now - _get_modified(info) > STALE_TEMP_AGE:
+                    self._remove(info["name"])
+            else:
+                entries.append(info)
+
+        total = sum(info["size"] for info in entries)
+
+        for info in sorted(entries, key=_get_modified):
+            if total <= target_size:
+                break
+
+            self._remove(info["name"])
+            total -= info["size"]
+
+        self._tracked_size = total
+
+    def evict_if_full(self) -> None:
+        """
+        Evicts rasters if the cache may have grown beyond `max_size`. The size is tracked as
+        rasters are added, so the cache is only listed on the first call and when it's full.
+        Rasters added by other processes are only counted once the cache is listed again.
+        """
+        if self.max_size is None:
+            return
+
+        if self._tracked_size is None:
+            self._tracked_size = self.size()
+
+        if self._tracked_size > self.max_size:
+            self.evict(int(self.max_size * EVICTION_TARGET))
+
+    def clear(self) -> None:
+        for info in self._list():
+            self._remove(info["name"])
+
+        self._tracked_size = 0
+
+
+class CachedRasterizer(BaseRasterizer):
+    """
+    Wraps a rasterizer so that pages of a document are read from a `RasterCache` when
+    possible, and only the missing pages are rendered
+    """
+
+    def __init__(self, rasterizer: BaseRasterizer, cache: RasterCache, document_hash: str, page_count: int) -> None:
+        self.rasterizer = rasterizer
+        self.cache = cache
+        self.document_hash = document_hash
+        self.page_count = page_count
+        self.name = rasterizer.name  # Rasters are identical whether or not they came from the cache
+
+    def is_available(self) -> bool:
+        return self.rasterizer.is_available()
+
+    def _rasterize(
+        self,
+        fp: Union[PathLike, str],
+        pages: Optional[List[int]],
+        *,
+        dpi: int,
+        device: RasterDevice,
+        downscale_factor: Optional[int],
+        quality: Optional[int],
+    ) -> Dict[int, bytes]:
+        key = {
+            "dpi": dpi,
+            "device": device,
+            "rasterizer": self.name,
+            "downscale_factor": downscale_factor,
+            "quality": quality,
+        }
+
+        rasters = {}
+        missing = []
+
+        for page_number in pages if pages is not None else range(1, self.page_count + 1):
+            data = self.cache.get(self.document_hash, page_number, **key)
+
+            if data is None:
+                missing.append(page_number)
+            else:
+                rasters[page_number] = data
+
+        if missing:
+            rendered = self.rasterizer._rasterize(
+                fp, missing, dpi=dpi, device=device, downscale_factor=downscale_factor, quality=quality
+            )
+
+            for page_number, data in rendered.items():
+                self.cache.put(self.document_hash, page_number, data, **key)
+                rasters[page_number] = data
+
+            self.cache.evict_if_full()
+
+        return dict(sorted(rasters.items()))
+
+
+_default_cache: Optional[RasterCache] = None
+_default_cache_configured = False
+
+
+def set_raster_cache(cache: Optional[Union[RasterCache, PathLike, str]]) -> None:
+    """
+    Sets the cache used by `Document.rasterize_page` and `rasterize_pdf`, given as a cache or a
+    local path or fsspec URL. Pass None to disable caching.
+    """
+    global _default_cache, _default_cache_configured
+
+    _default_cache = cache if cache is None or isinstance(cache, RasterCache) else RasterCache(cache)
+    _default_cache_configured = True
+
+
+def get_raster_cache() -> Optional[RasterCache]:
+    """
+    Returns the default raster cache, which is set with `set_raster_cache` or the
+    DOCPROMPT_RASTER_CACHE environment variable. Caching is disabled unless either is set.
+    """
+    global _default_cache, _default_cache_configured
+
+    if not _default_cache_configured:
+        path = os.getenv("DOCPROMPT_RASTER_CACHE")
+
+        _default_cache = RasterCache(path) if path else None
+        _default_cache_configured = True
+
+    return _default_cache
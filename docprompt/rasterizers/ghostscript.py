@@ -0,0 +1,68 @@
+import shutil
+from os import PathLike
+from typing import Dict, List, Optional, Union
+
+from docprompt._exec import ghostscript
+from docprompt._exec.pool import GhostscriptPool
+
+from .base import BaseRasterizer, RasterDevice
+
+
+class GhostscriptRasterizer(BaseRasterizer):
+    """
+    Rasterizes with Ghostscript. Pages are sharded across `max_workers` Ghostscript processes
+    (one per CPU by default), each of which is killed with a `GhostscriptTimeoutError` if it
+    runs longer than `timeout` seconds, and fails if it uses more than `max_memory` bytes.
+    """
+
+    name = "ghostscript"
+
+    def __init__(
+        self,
+        max_workers: Optional[int] = None,
+        *,
+        timeout: Optional[float] = ghostscript.DEFAULT_TIMEOUT,
+        max_memory: Optional[int] = None,
+    ) -> None:
+        self.limits = ghostscript.ProcessLimits(timeout=timeout, max_memory=max_memory)
+        self.pool = GhostscriptPool(max_workers, timeout=timeout, max_memory=max_memory)
+
+    def cancel(self) -> None:
+        """
+        Kills the Ghostscript processes rasterizing pages, from another thread
+        """
+        self.pool.cancel()
+
+    def is_available(self) -> bool:
+        return shutil.which(ghostscript.GS) is not None
+
+    def _rasterize(
+        self,
+        fp: Union[PathLike, str],
+        pages: Optional[List[int]],
+        *,
+        dpi: int,
+        device: RasterDevice,
+        downscale_factor: Optional[int],
+        quality: Optional[int],
+    ) -> Dict[int, bytes]:
+        if pages is None:
+            return self.pool.rasterize_pdf(
+                fp, dpi=dpi, device=device, downscale_factor=downscale_factor, quality=quality
+            )
+
+        return self.pool.rasterize_pages(
+            fp, pages, dpi=dpi, device=device, downscale_factor=downscale_factor, quality=quality
+        )
+
+    def rasterize_tiff(
+        self,
+        fp: Union[PathLike, str],
+        *,
+        dpi: int = 100,
+        device: RasterDevice = "tiffg4",
+        downscale_factor: Optional[int] = None,
+    ) -> bytes:
+        return ghostscript.rasterize_pdf_to_tiff(
+            fp, dpi=dpi, device=device, downscale_factor=downscale_factor, limits=self.limits
+        )
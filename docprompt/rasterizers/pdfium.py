@@ -0,0 +1,63 @@
+from os import PathLike
+from typing import Dict, List, Optional, Union
+
+from .base import GRAYSCALE_DEVICES, BaseRasterizer, RasterDevice, downscale_image, encode_image
+
+
+class PdfiumRasterizer(BaseRasterizer):
+    """
+    Rasterizes in-process with pypdfium2, which avoids spawning a process per call and is
+    permissively licensed. Requires the optional `pypdfium2` package.
+    """
+
+    name = "pdfium"
+
+    def is_available(self) -> bool:
+        try:
+            import pypdfium2  # noqa: F401
+        except ImportError:
+            return False
+
+        return True
+
+    def _rasterize(
+        self,
+        fp: Union[PathLike, str],
+        pages: Optional[List[int]],
+        *,
+        dpi: int,
+        device: RasterDevice,
+        downscale_factor: Optional[int],
+        quality: Optional[int],
+    ) -> Dict[int, bytes]:
+        try:
+            import pypdfium2 as pdfium
+        except ImportError as e:
+            raise ImportError(
+                "The pdfium rasterizer requires pypdfium2. Install it with `pip install docprompt[pdfium]`"
+            ) from e
+
+        pdf = pdfium.PdfDocument(str(fp))
+
+        try:
+            pdf.init_forms()  # Without this, filled form fields aren't drawn
+
+            rasters = {}
+
+            for page_number in pages if pages is not None else range(1, len(pdf) + 1):
+                if page_number < 1 or page_number > len(pdf):
+                    raise ValueError(f"Page number must be between 1 and {len(pdf)}")
+
+                page = pdf[page_number - 1]
+
+                try:
+                    bitmap = page.render(scale=dpi / 72, grayscale=device in GRAYSCALE_DEVICES)
+                    image = downscale_image(bitmap.to_pil(), downscale_factor)
+                finally:
+                    page.close()
+
+                rasters[page_number] = encode_image(image, device, quality=quality)
+        finally:
+            pdf.close()
+
+        return rasters
@@ -0,0 +1,55 @@
+import shutil
+from io import BytesIO
+from os import PathLike
+from typing import Dict, List, Optional, Union
+
+from PIL import Image
+
+from docprompt._exec import pdftoppm
+from docprompt._exec.ghostscript import group_page_runs
+
+from .base import GRAYSCALE_DEVICES, BaseRasterizer, RasterDevice, downscale_image, encode_image
+
+
+class PdftoppmRasterizer(BaseRasterizer):
+    """
+    Rasterizes with poppler's pdftoppm, which is GPL licensed and fast to start up
+    """
+
+    name = "pdftoppm"
+
+    def is_available(self) -> bool:
+        return shutil.which(pdftoppm.PDFTOPPM) is not None
+
+    def _rasterize(
+        self,
+        fp: Union[PathLike, str],
+        pages: Optional[List[int]],
+        *,
+        dpi: int,
+        device: RasterDevice,
+        downscale_factor: Optional[int],
+        quality: Optional[int],
+    ) -> Dict[int, bytes]:
+        gray = device in GRAYSCALE_DEVICES
+
+        if pages is None:
+            rasters = pdftoppm.rasterize_pdf_to_bytes(fp, dpi=dpi, gray=gray)
+        else:
+            rasters = {}
+
+            for first_page, last_page in group_page_runs(pages):
+                rasters.update(
+                    pdftoppm.rasterize_pdf_to_bytes(fp, dpi=dpi, gray=gray, first_page=first_page, last_page=last_page)
+                )
+
+        # pdftoppm's output already matches pnggray and png16m, so it only needs re-encoding for the other devices
+        if device not in ["pnggray", "png16m"] or downscale_factor:
+            rasters = {
+                page_number: encode_image(
+                    downscale_image(Image.open(BytesIO(raster)), downscale_factor), device, quality=quality
+                )
+                for page_number, raster in rasters.items()
+            }
+
+        return rasters
@@ -1,6 +1,6 @@
 import base64
 import gzip
-import pickle
+import re
 import tempfile
 from contextlib import contextmanager
 from datetime import datetime
@@ -8,19 +8,34 @@ from functools import cached_property
 from io import BytesIO
 from os import PathLike
 from pathlib import Path
-from typing import TYPE_CHECKING, Dict, List, Literal, Optional, Tuple, Union
+from typing import TYPE_CHECKING, Dict, Iterable, List, Literal, Optional, Tuple, Union
 
 import magic
-from PIL import ImageDraw
-from pydantic import BaseModel, Field, PositiveInt, computed_field, field_serializer, field_validator
+from PIL import Image, ImageDraw
+from pydantic import BaseModel, Field, PositiveInt, PrivateAttr, computed_field, field_serializer, field_validator
+from pypdf import PdfReader, PdfWriter
 
-from docprompt._exec.ghostscript import compress_pdf_to_bytes, rasterize_page_to_bytes, rasterize_pdf_to_bytes
+from docprompt._exec.ghostscript import compress_pdf_to_bytes
+from docprompt.rasterizers import BaseRasterizer, Raster, get_rasterizer
+from docprompt.rasterizers.cache import CachedRasterizer, RasterCache, get_raster_cache
 from docprompt.schema.operations import PageTextExtractionOutput
 
-from .layout import TextBlock
+from .layout import NormBBox, TextBlock
+from .page_geometry import PageGeometry
 
 if TYPE_CHECKING:
+    from docprompt.masking.redaction import RedactionRegion
+    from docprompt.masking.synthesis import ReplacementPolicy
     from docprompt.service_providers.base import BaseProvider
+    from docprompt.utils.annotations import Annotation
+    from docprompt.utils.classification import PageClassification
+    from docprompt.utils.diff import DocumentDiff
+    from docprompt.utils.embedded_images import EmbeddedImage
+    from docprompt.utils.fingerprint import DocumentFingerprint, PageFingerprint
+    from docprompt.utils.forms import FormField, FormFieldValue
+    from docprompt.utils.metadata import DocumentMetadata
+    from docprompt.utils.repair import RepairReport
+    from docprompt.utils.image import DPIInput, ImageSource
 
 import pdfplumber
 
@@ -29,20 +44,10 @@ DEFAULT_DPI = 100
 
 def get_page_render_size_from_bytes(file_bytes: bytes, page_number: int, dpi: int = DEFAULT_DPI):
     """
-    Returns the render size of a page in pixels
+    Returns the render size of a page (1-indexed) in pixels, accounting for its crop box,
+    rotation and user unit
     """
-    with pdfplumber.PDF.open(BytesIO(file_bytes)) as pdf:
-        page = pdf.pages[page_number]
-
-        width_pt, height_pt = page.mediabox.upper_right
-
-    width_in = width_pt / 72
-    height_in = height_pt / 72
-
-    width_px = int(width_in * dpi)
-    height_px = int(height_in * dpi)
-
-    return (width_px, height_px)
+    return PageGeometry.from_bytes(file_bytes, page_number).get_render_size(dpi)
 
 
 class Document(BaseModel):
@@ -52,12 +57,29 @@ class Document(BaseModel):
 
     name: str = Field(description="The name of the document")
     file_bytes: bytes = Field(description="The bytes of the document", repr=False)
-    file_path: Optional[str]
+    file_path: Optional[str] = None
+    source_mime_type: str = Field(
+        default="application/pdf", description="The MIME type of the original source, before any conversion to PDF"
+    )
     text_sidecars: Dict[str, Dict[int, PageTextExtractionOutput]] = Field(default_factory=dict, repr=False)
 
+    _repair_report: Optional["RepairReport"] = PrivateAttr(default=None)
+
     def __len__(self):
         return len(self.pages)
 
+    def __getitem__(self, index: Union[int, slice]) -> Union["DocumentPage", "Document"]:
+        """
+        Indexing a document returns a single page, while slicing it returns a new Document.
+
+        Indices follow Python sequence semantics over `pages`, so `document[0]` is page 1
+        and `document[2:5]` contains pages 3 through 5.
+        """
+        if isinstance(index, slice):
+            return Document.from_pages(self.pages[index], name=self.name)
+
+        return self.pages[index]
+
     def __hash__(self):
         return hash(self.document_hash)
 
@@ -71,6 +93,40 @@ class Document(BaseModel):
     def num_pages(self):
         return self.page_count
 
+    @cached_property
+    def pages(self) -> List["DocumentPage"]:
+        """
+        The pages of the document. Page numbers are 1-indexed, matching the text sidecars
+        """
+        reader = PdfReader(BytesIO(self.file_bytes))
+
+        pages = []
+
+        for page_number, pdf_page in enumerate(reader.pages, start=1):
+            geometry = PageGeometry.from_pdf_page(pdf_page)
+
+            pages.append(
+                DocumentPage(
+                    document=self,
+                    page_number=page_number,
+                    width=geometry.width,
+                    height=geometry.height,
+                    rotation=geometry.rotation,
+                    geometry=geometry,
+                )
+            )
+
+        return pages
+
+    def get_page(self, page_number: int) -> "DocumentPage":
+        """
+        Returns a single page of the document (1-indexed)
+        """
+        if page_number < 1 or page_number > self.num_pages:
+            raise ValueError(f"Page number must be between 1 and {self.num_pages}")
+
+        return self.pages[page_number - 1]
+
     @computed_field
     @cached_property
     def document_hash(self) -> str:
@@ -99,12 +155,16 @@ class Document(BaseModel):
             v = gzip.decompress(v)
 
         if magic.from_buffer(v, mime=True) != "application/pdf":
-            raise ValueError("File bytes must be a PDF")
+            raise ValueError("File bytes must be a PDF. Use `Document.from_image(s)` to load images")
 
         return v
 
     @classmethod
-    def from_path(cls, file_path: Union[PathLike, str]):
+    def from_path(cls, file_path: Union[PathLike, str], password: Optional[str] = None):
+        """
+        Loads a document from a path. Encrypted PDFs are decrypted with `password` (or the
+        empty password), raising `EncryptedDocumentError` if that fails.
+        """
         file_path = Path(file_path)
 
         if not file_path.is_file():
@@ -112,15 +172,229 @@ class Document(BaseModel):
 
         file_bytes = file_path.read_bytes()
 
+        if cls._is_image_bytes(file_bytes):
+            return cls.from_image(file_bytes, name=file_path.name, file_path=str(file_path))
+
+        file_bytes = cls._decrypt_bytes(file_bytes, password)
+
         return cls(name=file_path.name, file_path=str(file_path), file_bytes=file_bytes)
 
     @classmethod
-    def from_bytes(cls, file_bytes: bytes, name: Optional[str] = None):
+    def from_bytes(cls, file_bytes: bytes, name: Optional[str] = None, password: Optional[str] = None):
+        """
+        Loads a document from bytes. Encrypted PDFs are decrypted with `password` (or the
+        empty password), raising `EncryptedDocumentError` if that fails.
+        """
+        if cls._is_image_bytes(file_bytes):
+            return cls.from_image(file_bytes, name=name)
+
         if name is None:
             name = f"PDF-{datetime.now().isoformat()}.pdf"
 
+        file_bytes = cls._decrypt_bytes(file_bytes, password)
+
         return cls(name=name, file_bytes=file_bytes)
 
+    @staticmethod
+    def _decrypt_bytes(file_bytes: bytes, password: Optional[str] = None) -> bytes:
+        from docprompt.utils.encryption import decrypt_pdf_bytes
+
+        return decrypt_pdf_bytes(file_bytes, password)
+
+    @property
+    def is_encrypted(self) -> bool:
+        from docprompt.utils.encryption import is_encrypted_pdf
+
+        return is_encrypted_pdf(self.file_bytes)
+
+    @property
+    def repair_report(self) -> Optional["RepairReport"]:
+        """
+        The report of the repair run when the document was loaded with `repair=True` or
+        returned by `repair_document`, if any
+        """
+        return self._repair_report
+
+    def decrypt(self, password: Optional[str] = None) -> "Document":
+        """
+        Returns a decrypted copy of the document, so that rasterization, splitting and
+        providers work normally. Raises `EncryptedDocumentError` if the password is wrong.
+        """
+        return self._with_file_bytes(self._decrypt_bytes(self.file_bytes, password))
+
+    def _with_file_bytes(self, file_bytes: bytes) -> "Document":
+        """
+        Returns a copy of the document with new bytes for the same pages, carrying along the text sidecars
+        """
+        return self.__class__(
+            name=self.name,
+            file_bytes=file_bytes,
+            file_path=self.file_path,
+            source_mime_type=self.source_mime_type,
+            text_sidecars={
+                provider_name: {number: page_text.model_copy(deep=True) for number, page_text in sidecars.items()}
+                for provider_name, sidecars in self.text_sidecars.items()
+            },
+        )
+
+    @classmethod
+    def from_image(
+        cls,
+        image: "ImageSource",
+        name: Optional[str] = None,
+        *,
+        file_path: Optional[str] = None,
+        dpi: Optional["DPIInput"] = None,
+    ):
+        """
+        Creates a document from a single image. Multi-page TIFFs produce one page per frame.
+        """
+        return cls.from_images([image], name=name, file_path=file_path, dpi=dpi)
+
+    @classmethod
+    def from_images(
+        cls,
+        images: List["ImageSource"],
+        name: Optional[str] = None,
+        *,
+        file_path: Optional[str] = None,
+        dpi: Optional["DPIInput"] = None,
+    ):
+        """
+        Creates a document from one or more images (PNG, JPEG or TIFF), one page per image frame.
+
+        The images are wrapped into a PDF at their original pixel resolution. Pass `dpi` to
+        override the resolution recorded in the images' metadata.
+        """
+        from PIL import Image
+
+        from docprompt.utils.image import get_image_mime_type, images_to_pdf_bytes
+
+        if len(images) == 0:
+            raise ValueError("Must provide at least one image")
+
+        mime_types = set()
+
+        for image in images:
+            if isinstance(image, Image.Image):
+                mime_types.add(Image.MIME.get(image.format) if image.format else None)
+            else:
+                mime_type = get_image_mime_type(image)
+
+                if mime_type is None:
+                    raise ValueError("Images must be PNG, JPEG or TIFF")
+
+                mime_types.add(mime_type)
+
+                if name is None and not isinstance(image, bytes):
+                    name = Path(image).name
+
+        # Only record a source MIME type if all of the images agree on one
+        source_mime_type = mime_types.pop() if len(mime_types) == 1 else None
+
+        if name is None:
+            name = f"PDF-{datetime.now().isoformat()}.pdf"
+        else:
+            name = str(Path(name).with_suffix(".pdf"))
+
+        file_bytes = images_to_pdf_bytes(images, dpi=dpi)
+
+        return cls(
+            name=name,
+            file_path=file_path,
+            file_bytes=file_bytes,
+            source_mime_type=source_mime_type or "image/*",
+        )
+
+    @staticmethod
+    def _is_image_bytes(file_bytes: bytes) -> bool:
+        from docprompt.utils.image import is_image
+
+        return is_image(file_bytes)
+
+    @property
+    def is_image_source(self) -> bool:
+        """
+        Whether the document was created from one or more images rather than a PDF
+        """
+        return self.source_mime_type.startswith("image/")
+
+    @classmethod
+    def from_pages(cls, pages: List["DocumentPage"], name: Optional[str] = None) -> "Document":
+        """
+        Creates a new document from pages of one or more documents, in the given order.
+
+        Text sidecars are carried along and renumbered to match the new page order.
+        """
+        if len(pages) == 0:
+            raise ValueError("Must provide at least one page")
+
+        writer = PdfWriter()
+        readers: Dict[int, PdfReader] = {}
+        text_sidecars: Dict[str, Dict[int, PageTextExtractionOutput]] = {}
+
+        for new_page_number, page in enumerate(pages, start=1):
+            reader_key = id(page.document)
+
+            if reader_key not in readers:
+                readers[reader_key] = PdfReader(BytesIO(page.document.file_bytes))
+
+            writer.add_page(readers[reader_key].pages[page.page_number - 1])
+
+            for provider_name, page_text in page.text_sidecars.items():
+                text_sidecars.setdefault(provider_name, {})[new_page_number] = page_text.model_copy(deep=True)
+
+        output_stream = BytesIO()
+        writer.write(output_stream)
+
+        source_mime_types = {page.document.source_mime_type for page in pages}
+
+        return cls(
+            name=name or pages[0].document.name,
+            file_bytes=output_stream.getvalue(),
+            source_mime_type=source_mime_types.pop() if len(source_mime_types) == 1 else "application/pdf",
+            text_sidecars=text_sidecars,
+        )
+
+    @classmethod
+    def concat(cls, documents: List["Document"], name: Optional[str] = None) -> "Document":
+        """
+        Concatenates several documents into a new document, carrying along their text sidecars
+        """
+        if len(documents) == 0:
+            raise ValueError("Must provide at least one document")
+
+        return cls.from_pages([page for document in documents for page in document.pages], name=name)
+
+    def reorder(self, page_numbers: List[int]) -> "Document":
+        """
+        Returns a new document with the pages in the given order (1-indexed).
+
+        Every page must appear exactly once.
+        """
+        if sorted(page_numbers) != list(range(1, self.num_pages + 1)):
+            raise ValueError(f"Page order must contain each page number from 1 to {self.num_pages} exactly once")
+
+        return Document.from_pages([self.get_page(page_number) for page_number in page_numbers], name=self.name)
+
+    def delete_pages(self, page_numbers: List[int]) -> "Document":
+        """
+        Returns a new document without the given pages (1-indexed)
+        """
+        to_delete = set(page_numbers)
+
+        for page_number in to_delete:
+            if page_number < 1 or page_number > self.num_pages:
+                raise ValueError(f"Page number must be between 1 and {self.num_pages}")
+
+        if len(to_delete) == self.num_pages:
+            raise ValueError("Cannot delete every page of a document")
+
+        return Document.from_pages(
+            [page for page in self.pages if page.page_number not in to_delete],
+            name=self.name,
+        )
+
     def get_bytes(self) -> bytes:
         return self.file_bytes  # Deprecated
 
@@ -130,9 +404,9 @@ class Document(BaseModel):
 
     def get_page_render_size(self, page_number: int, dpi: int = DEFAULT_DPI) -> Tuple[int, int]:
         """
-        Returns the render size of a page in pixels
+        Returns the render size of a page (1-indexed) in pixels
         """
-        return get_page_render_size_from_bytes(self.get_bytes(), page_number, dpi=dpi)
+        return self.get_page(page_number).get_render_size(dpi=dpi)
 
     def to_compressed_bytes(self, compression_kwargs: dict = {}) -> bytes:
         """
@@ -141,24 +415,171 @@ class Document(BaseModel):
         with self.as_tempfile() as temp_path:
             return compress_pdf_to_bytes(temp_path, **compression_kwargs)
 
-    def rasterize_page(self, page_number: int, dpi: int = DEFAULT_DPI, device="png16m") -> bytes:
+    def _get_rasterizer(
+        self, rasterizer: Optional[Union[str, BaseRasterizer]], cache: Optional[Union[RasterCache, bool]]
+    ) -> BaseRasterizer:
+        """
+        Resolves a rasterizer, wrapped to use the raster cache unless caching is disabled
+        """
+        rasterizer = get_rasterizer(rasterizer)
+
+        if cache is None or cache is True:
+            cache = get_raster_cache()
+
+        if not cache:
+            return rasterizer
+
+        return CachedRasterizer(rasterizer, cache, self.document_hash, self.num_pages)
+
+    def rasterize_page(
+        self,
+        page_number: int,
+        dpi: int = DEFAULT_DPI,
+        device="png16m",
+        rasterizer: Optional[Union[str, BaseRasterizer]] = None,
+        quality: Optional[int] = None,
+        cache: Optional[Union[RasterCache, bool]] = None,
+    ) -> bytes:
+        """
+        Rasterizes a page of the document. Uses the default rasterizer (Ghostscript unless
+        configured otherwise) if `rasterizer` is not given.
+
+        `device` selects the output format: pnggray, png16m, png256 and pngmono for PNG, jpeg and
+        jpeggray for JPEG, tiffg4, tifflzw, tiffgray and tiff24nc for TIFF, or webp. `quality`
+        applies to JPEG and WebP output.
+
+        Rasters are read from and written to `cache` if given, or the default raster cache if
+        one is configured (see `docprompt.rasterizers.cache`). Pass `cache=False` to bypass it.
+        """
+        if page_number < 1 or page_number > self.num_pages:
+            raise ValueError(f"Page number must be between 1 and {self.num_pages}")
+
+        with self.as_tempfile() as temp_path:
+            return self._get_rasterizer(rasterizer, cache).rasterize_page(
+                temp_path, page_number, dpi=dpi, device=device, quality=quality
+            )
+
+    def rasterize_page_to_size(
+        self,
+        page_number: int,
+        max_width: Optional[int] = None,
+        max_height: Optional[int] = None,
+        max_pixels: Optional[int] = None,
+        device="png16m",
+        rasterizer: Optional[Union[str, BaseRasterizer]] = None,
+        quality: Optional[int] = None,
+        cache: Optional[Union[RasterCache, bool]] = None,
+    ) -> Raster:
         """
-        Rasterizes a page of the document using Ghostscript
+        Rasterizes a page at the highest DPI at which it fits within `max_width`, `max_height` and
+        `max_pixels`, at least one of which must be given. The returned `Raster` carries the DPI
+        that was picked. Devices and caching work as in `rasterize_page`.
         """
-        if page_number < 0 or page_number > self.num_pages:
-            raise ValueError(f"Page number must be between 0 and {self.num_pages}")
+        if page_number < 1 or page_number > self.num_pages:
+            raise ValueError(f"Page number must be between 1 and {self.num_pages}")
 
         with self.as_tempfile() as temp_path:
-            return rasterize_page_to_bytes(temp_path, page_number, dpi=dpi, device=device)
+            rasters = self._get_rasterizer(rasterizer, cache).rasterize_to_size(
+                temp_path,
+                [page_number],
+                max_width=max_width,
+                max_height=max_height,
+                max_pixels=max_pixels,
+                device=device,
+                quality=quality,
+            )
+
+        return rasters[page_number]
+
+    def extract_page_images(self, page_number: int, **kwargs) -> List["EmbeddedImage"]:
+        """
+        Extracts the images embedded in a page at their native resolution, along with
+        where they are drawn. See `docprompt.utils.embedded_images.extract_page_images`
+        """
+        from docprompt.utils.embedded_images import extract_page_images
+
+        return extract_page_images(self.file_bytes, page_number, **kwargs)
+
+    def _validate_pages(self, pages: Optional[Iterable[int]]) -> Optional[List[int]]:
+        if pages is None:
+            return None
+
+        pages = sorted(set(pages))
+
+        if pages and (pages[0] < 1 or pages[-1] > self.num_pages):
+            raise ValueError(f"Page numbers must be between 1 and {self.num_pages}")
+
+        return pages
 
     def rasterize_pdf(
-        self, dpi: int = DEFAULT_DPI, device="pnggray", downscale_factor: Optional[int] = None
+        self,
+        dpi: int = DEFAULT_DPI,
+        device="pnggray",
+        downscale_factor: Optional[int] = None,
+        rasterizer: Optional[Union[str, BaseRasterizer]] = None,
+        quality: Optional[int] = None,
+        pages: Optional[Iterable[int]] = None,
+        cache: Optional[Union[RasterCache, bool]] = None,
     ) -> Dict[int, bytes]:
         """
-        Rasterizes the entire document using Ghostscript
+        Rasterizes the entire document, using the default rasterizer if `rasterizer` is not given.
+        See `rasterize_page` for the supported devices.
+
+        Pass `pages` (e.g. `range(40, 61)`) to only rasterize those pages. The result is keyed by
+        page number either way. Caching works as in `rasterize_page`.
+        """
+        pages = self._validate_pages(pages)
+
+        with self.as_tempfile() as temp_path:
+            return self._get_rasterizer(rasterizer, cache).rasterize_pdf(
+                temp_path, dpi=dpi, device=device, downscale_factor=downscale_factor, quality=quality, pages=pages
+            )
+
+    def rasterize_pdf_to_size(
+        self,
+        max_width: Optional[int] = None,
+        max_height: Optional[int] = None,
+        max_pixels: Optional[int] = None,
+        device="pnggray",
+        downscale_factor: Optional[int] = None,
+        rasterizer: Optional[Union[str, BaseRasterizer]] = None,
+        quality: Optional[int] = None,
+        pages: Optional[Iterable[int]] = None,
+        cache: Optional[Union[RasterCache, bool]] = None,
+    ) -> Dict[int, Raster]:
+        """
+        Rasterizes the entire document (or `pages`) to fit within `max_width`, `max_height` and
+        `max_pixels`, as in `rasterize_page_to_size`. The DPI is picked separately for each page.
+        """
+        pages = self._validate_pages(pages)
+
+        with self.as_tempfile() as temp_path:
+            return self._get_rasterizer(rasterizer, cache).rasterize_to_size(
+                temp_path,
+                pages,
+                max_width=max_width,
+                max_height=max_height,
+                max_pixels=max_pixels,
+                device=device,
+                downscale_factor=downscale_factor,
+                quality=quality,
+            )
+
+    def rasterize_tiff(
+        self,
+        dpi: int = DEFAULT_DPI,
+        device="tiffg4",
+        downscale_factor: Optional[int] = None,
+        rasterizer: Optional[Union[str, BaseRasterizer]] = None,
+    ) -> bytes:
+        """
+        Rasterizes the entire document to a single multi-page TIFF, which is bilevel and G4
+        compressed by default
         """
         with self.as_tempfile() as temp_path:
-            return rasterize_pdf_to_bytes(temp_path, dpi=dpi, device=device, downscale_factor=downscale_factor)
+            return get_rasterizer(rasterizer).rasterize_tiff(
+                temp_path, dpi=dpi, device=device, downscale_factor=downscale_factor
+            )
 
     @contextmanager
     def as_tempfile(self, **kwargs) -> str:
@@ -184,6 +605,24 @@ class Document(BaseModel):
         with path.open("wb") as f:
             f.write(self.file_bytes)
 
+    def save_bundle(self, path: Union[PathLike, str], **kwargs):
+        """
+        Saves the document and its text sidecars as a versioned zip bundle.
+        See `docprompt.utils.bundle.save_bundle`
+        """
+        from docprompt.utils.bundle import save_bundle
+
+        save_bundle(self, path, **kwargs)
+
+    @classmethod
+    def load_bundle(cls, path: Union[PathLike, str], **kwargs) -> "Document":
+        """
+        Loads a document saved with `save_bundle`, validating its hash
+        """
+        from docprompt.utils.bundle import load_bundle
+
+        return load_bundle(path, **kwargs)
+
     @property
     def text_data(self):
         try:
@@ -191,14 +630,146 @@ class Document(BaseModel):
         except StopIteration:
             return None
 
+    def fingerprint(self, provider_name: Optional[str] = None) -> "DocumentFingerprint":
+        """
+        Computes perceptual and text shingle hashes of every page, which stay similar across
+        re-saved or re-scanned copies. See `docprompt.utils.fingerprint.find_near_duplicates`
+        """
+        from docprompt.utils.fingerprint import fingerprint_document
+
+        return fingerprint_document(self, provider_name)
+
+    def diff(self, other: "Document", **kwargs) -> "DocumentDiff":
+        """
+        Compares this document (as revision A) with another (as revision B), reporting inserted,
+        deleted and moved words. See `docprompt.utils.diff.diff_documents`
+        """
+        from docprompt.utils.diff import diff_documents
+
+        return diff_documents(self, other, **kwargs)
+
+    def classify_pages(self, pages: Optional[List[int]] = None) -> Dict[int, "PageClassification"]:
+        """
+        Classifies each page as born-digital, scanned, scanned with a good or bad hidden
+        text layer, or blank, using the PDF's text layer and image placement
+        """
+        from docprompt.utils.classification import classify_pdf_pages
+
+        return classify_pdf_pages(self.file_bytes, pages)
+
+    @cached_property
+    def metadata(self) -> "DocumentMetadata":
+        """
+        The Info dictionary, XMP metadata, bookmark outline and page labels of the document
+        """
+        from docprompt.utils.metadata import get_document_metadata
+
+        return get_document_metadata(self.file_bytes)
+
+    def get_page_number_for_label(self, label: str) -> Optional[int]:
+        """
+        Returns the page number (1-indexed) of the first page with the given logical label, e.g. "iv"
+        """
+        for page_number, page_label in enumerate(self.metadata.page_labels, start=1):
+            if page_label == label:
+                return page_number
+
+        return None
+
+    @cached_property
+    def form_fields(self) -> List["FormField"]:
+        """
+        The AcroForm fields of the document, one per widget, in page order
+        """
+        from docprompt.utils.forms import get_form_fields
+
+        return get_form_fields(self.file_bytes)
+
+    def fill_form(self, values: Dict[str, "FormFieldValue"]) -> "Document":
+        """
+        Returns a new document with the given form fields filled, keyed by fully qualified name.
+        See `docprompt.utils.forms.fill_form`
+        """
+        from docprompt.utils.forms import fill_form
+
+        return fill_form(self, values)
+
+    @cached_property
+    def annotations(self) -> List["Annotation"]:
+        """
+        The annotations of the document (highlights, notes, links, etc.), in page order
+        """
+        from docprompt.utils.annotations import get_annotations
+
+        return get_annotations(self.file_bytes)
+
+    def annotate(self, blocks: Dict[int, List[TextBlock]], **kwargs) -> "Document":
+        """
+        Returns a new document with the given text blocks (per page) marked with highlight
+        or rectangle annotations. See `docprompt.utils.annotations.annotate_document`
+        """
+        from docprompt.utils.annotations import annotate_document
+
+        return annotate_document(self, blocks, **kwargs)
+
+    def redact(
+        self,
+        regions: Optional[Dict[int, List["RedactionRegion"]]] = None,
+        *,
+        patterns: Optional[List[Union[str, "re.Pattern"]]] = None,
+        provider_name: Optional[str] = None,
+        **kwargs,
+    ) -> "Document":
+        """
+        Returns a new document with the given regions (per page) and/or regex matches over
+        the text sidecar burned in as black boxes. See `docprompt.masking.redaction.redact_document`
+        """
+        from docprompt.masking.redaction import redact_document
+
+        return redact_document(self, regions, patterns=patterns, provider_name=provider_name, **kwargs)
+
+    def synthesize(
+        self, blocks: Dict[int, List[TextBlock]], policy: Optional["ReplacementPolicy"] = None, **kwargs
+    ) -> "Document":
+        """
+        Returns a new document with the given text blocks (per page) replaced by realistic
+        synthetic text. See `docprompt.masking.synthesis.synthesize_document`
+        """
+        from docprompt.masking.synthesis import synthesize_document
+
+        return synthesize_document(self, blocks, policy, **kwargs)
+
+    def to_searchable_pdf(self, provider_name: Optional[str] = None) -> "Document":
+        """
+        Returns a new document with an invisible, searchable text layer built from the
+        word-level text sidecar of the given provider (the first provider by default)
+        """
+        from docprompt.utils.searchable import make_searchable_pdf
+
+        return make_searchable_pdf(self, provider_name)
+
     def perform_text_extraction(
-        self, provider: "BaseProvider", cache: bool = True
+        self,
+        provider: "BaseProvider",
+        cache: bool = True,
+        *,
+        pages: Optional[List[int]] = None,
+        only_pages_needing_ocr: bool = False,
     ) -> Dict[int, PageTextExtractionOutput]:
         """
         Performs text extraction for a given provider
+
+        If `only_pages_needing_ocr` is set, pages are classified first and only those
+        without a usable text layer are sent to the provider.
         """
+        if only_pages_needing_ocr:
+            classifications = self.classify_pages(pages)
+            pages = [page_number for page_number, result in classifications.items() if result.needs_ocr]
 
-        result = provider.process_document(self)
+            if not pages:
+                return {}
+
+        result = provider.process_document(self, pages=pages)
 
         sidecars = {}
 
@@ -212,6 +783,192 @@ class Document(BaseModel):
             sidecars[page_result.page_number] = page_result.ocr_result
 
         if cache:
-            self.text_sidecars[provider.name] = sidecars
+            self.text_sidecars.setdefault(provider.name, {}).update(sidecars)
 
         return sidecars
+
+
+class DocumentPage(BaseModel):
+    """
+    Represents a single page of a Document
+    """
+
+    document: Document = Field(description="The document this page belongs to", repr=False, exclude=True)
+    page_number: PositiveInt = Field(description="The 1-indexed page number")
+    width: float = Field(description="The displayed width of the page in points, after rotation")
+    height: float = Field(description="The displayed height of the page in points, after rotation")
+    rotation: int = Field(default=0, description="The clockwise rotation of the page in degrees")
+    geometry: PageGeometry = Field(description="The boxes, rotation and user unit of the page", repr=False)
+
+    _raster_cache: Dict[Tuple[int, str, str], bytes] = PrivateAttr(default_factory=dict)
+
+    def get_render_size(self, dpi: int = DEFAULT_DPI) -> Tuple[int, int]:
+        """
+        Returns the render size of the page in pixels
+        """
+        return self.geometry.get_render_size(dpi)
+
+    def rasterize(
+        self, dpi: int = DEFAULT_DPI, device="png16m", rasterizer: Optional[Union[str, BaseRasterizer]] = None
+    ) -> bytes:
+        """
+        Rasterizes the page, caching the result for subsequent calls with the same settings
+        """
+        rasterizer = get_rasterizer(rasterizer)
+        key = (dpi, device, rasterizer.name)
+
+        if key not in self._raster_cache:
+            self._raster_cache[key] = self.document.rasterize_page(
+                self.page_number, dpi=dpi, device=device, rasterizer=rasterizer
+            )
+
+        return self._raster_cache[key]
+
+    def get_image(
+        self, dpi: int = DEFAULT_DPI, device="png16m", rasterizer: Optional[Union[str, BaseRasterizer]] = None
+    ) -> Image.Image:
+        """
+        Returns the rasterized page as a PIL image
+        """
+        return Image.open(BytesIO(self.rasterize(dpi=dpi, device=device, rasterizer=rasterizer)))
+
+    @property
+    def image(self) -> Image.Image:
+        return self.get_image()
+
+    @property
+    def label(self) -> str:
+        """
+        The logical label of the page, e.g. "iv" or "A-3", which is what readers usually cite
+        """
+        page_labels = self.document.metadata.page_labels
+
+        if len(page_labels) >= self.page_number:
+            return page_labels[self.page_number - 1]
+
+        return str(self.page_number)
+
+    def extract_images(self, **kwargs) -> List["EmbeddedImage"]:
+        """
+        Returns the images embedded in the page at their native resolution, along with where they are drawn
+        """
+        return self.document.extract_page_images(self.page_number, **kwargs)
+
+    @property
+    def text_sidecars(self) -> Dict[str, PageTextExtractionOutput]:
+        """
+        The text extraction results for this page, keyed by provider name
+        """
+        return {
+            provider_name: sidecars[self.page_number]
+            for provider_name, sidecars in self.document.text_sidecars.items()
+            if self.page_number in sidecars
+        }
+
+    def get_text_data(self, provider_name: Optional[str] = None) -> Optional[PageTextExtractionOutput]:
+        """
+        Returns the text extraction result for this page, from the first provider if none is specified
+        """
+        if provider_name is None:
+            return next(iter(self.text_sidecars.values()), None)
+
+        return self.text_sidecars.get(provider_name)
+
+    @property
+    def text_data(self) -> Optional[PageTextExtractionOutput]:
+        return self.get_text_data()
+
+    @property
+    def text(self) -> str:
+        text_data = self.text_data
+
+        return text_data.text if text_data else ""
+
+    def fingerprint(self, provider_name: Optional[str] = None) -> "PageFingerprint":
+        """
+        Computes perceptual and text shingle hashes of the page
+        """
+        from docprompt.utils.fingerprint import fingerprint_page
+
+        return fingerprint_page(self, provider_name)
+
+    def classify(self) -> "PageClassification":
+        """
+        Classifies the page as born-digital, scanned, scanned with a good or bad hidden text layer, or blank
+        """
+        return self.document.classify_pages([self.page_number])[self.page_number]
+
+    @property
+    def form_fields(self) -> List["FormField"]:
+        return [field for field in self.document.form_fields if field.page_number == self.page_number]
+
+    @property
+    def annotations(self) -> List["Annotation"]:
+        return [annotation for annotation in self.document.annotations if annotation.page_number == self.page_number]
+
+    def get_form_field_blocks(self) -> List[TextBlock]:
+        """
+        Returns the values of the filled form fields on this page as text blocks
+        """
+        return [field.to_text_block() for field in self.form_fields if field.display_value.strip()]
+
+    def get_text_blocks(
+        self,
+        level: Literal["word", "line", "block"] = "word",
+        provider_name: Optional[str] = None,
+        include_form_fields: bool = False,
+    ) -> List[TextBlock]:
+        """
+        Returns the text blocks for this page at the given level
+
+        If `include_form_fields` is set, the values of filled form fields are appended as
+        blocks, since they are usually missing from both the text layer and OCR results.
+        """
+        text_data = self.get_text_data(provider_name)
+        text_blocks = list(getattr(text_data, f"{level}s")) if text_data is not None else []
+
+        if include_form_fields:
+            text_blocks += self.get_form_field_blocks()
+
+        return text_blocks
+
+    def crop_image(self, bbox: NormBBox, dpi: int = DEFAULT_DPI, device="png16m") -> Image.Image:
+        """
+        Returns the region of the rasterized page covered by a normalized bounding box
+        """
+        image = self.get_image(dpi=dpi, device=device)
+
+        return image.crop(self.geometry.bbox_to_pixels(bbox, dpi))
+
+    def draw_text_blocks(
+        self,
+        text_blocks: Optional[List[TextBlock]] = None,
+        *,
+        dpi: int = DEFAULT_DPI,
+        outline: str = "red",
+        width: int = 2,
+    ) -> Image.Image:
+        """
+        Returns the rasterized page with the given text blocks (or the page's words) outlined
+        """
+        if text_blocks is None:
+            text_blocks = self.get_text_blocks("word")
+
+        image = self.get_image(dpi=dpi).convert("RGB")
+        image_width, image_height = image.size
+
+        draw = ImageDraw.Draw(image)
+
+        for block in text_blocks:
+            if block.geometry.bounding_poly is not None:
+                vertices = block.geometry.bounding_poly.normalized_vertices
+                draw.polygon([(v.x * image_width, v.y * image_height) for v in vertices], outline=outline, width=width)
+            else:
+                bbox = block.bounding_box
+                draw.rectangle(
+                    (bbox.x0 * image_width, bbox.top * image_height, bbox.x1 * image_width, bbox.bottom * image_height),
+                    outline=outline,
+                    width=width,
+                )
+
+        return image
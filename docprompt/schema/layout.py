@@ -201,6 +201,21 @@ class BoundingPoly(BaseModel):
     def __getitem__(self, index):
         return self.normalized_vertices[index]
 
+    @classmethod
+    def from_norm_bbox(cls, bbox: NormBBox):
+        """
+        Returns an axis-aligned BoundingPoly from a NormBBox, with vertices ordered
+        top-left, top-right, bottom-right, bottom-left
+        """
+        return cls(
+            normalized_vertices=[
+                Point(x=bbox.x0, y=bbox.top),
+                Point(x=bbox.x1, y=bbox.top),
+                Point(x=bbox.x1, y=bbox.bottom),
+                Point(x=bbox.x0, y=bbox.bottom),
+            ]
+        )
+
     def get_skew_angle(self):
         """
         Determines the skew angle (in degrees) of the bounding poly from
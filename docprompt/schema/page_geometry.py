@@ -0,0 +1,289 @@
+from io import BytesIO
+from math import ceil, sqrt
+from typing import TYPE_CHECKING, Optional, Tuple
+
+from pydantic import BaseModel, Field
+from pypdf import PageObject, PdfReader
+
+from .layout import NormBBox
+
+if TYPE_CHECKING:
+    from pdfplumber.page import Page
+
+Box = Tuple[float, float, float, float]
+
+
+def _normalize_box(box) -> Box:
+    x0, y0, x1, y1 = (float(v) for v in box)
+
+    return min(x0, x1), min(y0, y1), max(x0, x1), max(y0, y1)
+
+
+def _clamp(value: float) -> float:
+    return min(max(value, 0.0), 1.0)
+
+
+class PageGeometry(BaseModel):
+    """
+    The geometry of a PDF page, and conversions between the coordinate spaces we work in:
+
+    - PDF user space: the page's own coordinates, origin at the bottom-left of the unrotated
+      page, in user space units (1/72 inch, times the page's UserUnit)
+    - Points: the displayed page in 1/72 inch, origin at the top-left, after the crop box and
+      rotation are applied
+    - Pixels: a raster of the displayed page at a given DPI, as rendered by Ghostscript
+    - Normalized: the displayed page scaled to [0, 1] on both axes, as used by NormBBox
+    - Layout: pdfminer's coordinates, which pdfplumber reports. The media box is rotated and
+      its corner moved to the origin, and pdfplumber measures `top` and `bottom` down from its top.
+    """
+
+    mediabox: Box = Field(description="The media box as (x0, y0, x1, y1) in user space")
+    cropbox: Box = Field(description="The visible region as (x0, y0, x1, y1) in user space, clipped to the media box")
+    rotation: int = Field(default=0, description="The clockwise display rotation in degrees: 0, 90, 180 or 270")
+    user_unit: float = Field(default=1.0, description="The size of a user space unit, in multiples of 1/72 inch")
+
+    @classmethod
+    def _from_boxes(cls, mediabox, cropbox, rotation: int, user_unit: float) -> "PageGeometry":
+        mediabox = _normalize_box(mediabox)
+        cropbox = _normalize_box(cropbox)
+
+        # The spec clips the crop box to the media box, and viewers fall back to the media box if that leaves nothing
+        cropbox = (
+            max(cropbox[0], mediabox[0]),
+            max(cropbox[1], mediabox[1]),
+            min(cropbox[2], mediabox[2]),
+            min(cropbox[3], mediabox[3]),
+        )
+
+        if cropbox[0] >= cropbox[2] or cropbox[1] >= cropbox[3]:
+            cropbox = mediabox
+
+        return cls(
+            mediabox=mediabox,
+            cropbox=cropbox,
+            rotation=round(rotation / 90) * 90 % 360,
+            user_unit=user_unit if user_unit > 0 else 1.0,
+        )
+
+    @classmethod
+    def from_pdf_page(cls, pdf_page: PageObject) -> "PageGeometry":
+        user_unit = float(pdf_page["/UserUnit"]) if "/UserUnit" in pdf_page else 1.0
+
+        return cls._from_boxes(pdf_page.mediabox, pdf_page.cropbox, pdf_page.rotation, user_unit)
+
+    @classmethod
+    def from_pdfplumber_page(cls, page: "Page") -> "PageGeometry":
+        """
+        Reads the geometry of a pdfplumber page from the page dictionary pdfminer already parsed
+        """
+        from pdfminer.pdftypes import resolve1
+
+        page_obj = page.page_obj
+        user_unit = float(resolve1(page_obj.attrs.get("UserUnit", 1.0)))
+
+        return cls._from_boxes(page_obj.mediabox, page_obj.cropbox, page_obj.rotate, user_unit)
+
+    @classmethod
+    def from_bytes(cls, file_bytes: bytes, page_number: int) -> "PageGeometry":
+        """
+        Reads the geometry of a page (1-indexed) of a PDF
+        """
+        reader = PdfReader(BytesIO(file_bytes))
+
+        if page_number < 1 or page_number > len(reader.pages):
+            raise ValueError(f"Page number must be between 1 and {len(reader.pages)}")
+
+        return cls.from_pdf_page(reader.pages[page_number - 1])
+
+    @property
+    def is_rotated_sideways(self) -> bool:
+        return self.rotation in (90, 270)
+
+    @property
+    def width(self) -> float:
+        """
+        The displayed width of the page in points
+        """
+        x0, y0, x1, y1 = self.cropbox
+        width = (y1 - y0) if self.is_rotated_sideways else (x1 - x0)
+
+        return width * self.user_unit
+
+    @property
+    def height(self) -> float:
+        """
+        The displayed height of the page in points
+        """
+        x0, y0, x1, y1 = self.cropbox
+        height = (x1 - x0) if self.is_rotated_sideways else (y1 - y0)
+
+        return height * self.user_unit
+
+    def get_render_size(self, dpi: float) -> Tuple[int, int]:
+        """
+        Returns the size in pixels of the page rasterized at the given DPI, rounded the same
+        way Ghostscript rounds its page size
+        """
+        return max(1, int(self.width * dpi / 72 + 0.5)), max(1, int(self.height * dpi / 72 + 0.5))
+
+    def get_dpi_for_size(
+        self, max_width: Optional[int] = None, max_height: Optional[int] = None, max_pixels: Optional[int] = None
+    ) -> int:
+        """
+        Returns the highest whole DPI at which the rasterized page fits within the given
+        width, height and total pixel count
+        """
+        if max_width is None and max_height is None and max_pixels is None:
+            raise ValueError("At least one of max_width, max_height or max_pixels must be given")
+
+        limits = []
+
+        if max_width is not None:
+            limits.append(max_width * 72 / self.width)
+
+        if max_height is not None:
+            limits.append(max_height * 72 / self.height)
+
+        if max_pixels is not None:
+            limits.append(72 * sqrt(max_pixels / (self.width * self.height)))
+
+        def fits(dpi: int) -> bool:
+            width, height = self.get_render_size(dpi)
+
+            return (
+                (max_width is None or width <= max_width)
+                and (max_height is None or height <= max_height)
+                and (max_pixels is None or width * height <= max_pixels)
+            )
+
+        # Rounding the render size can push it either side of the limit, so nudge the estimate until it fits exactly
+        dpi = max(1, int(min(limits)))
+
+        while dpi > 1 and not fits(dpi):
+            dpi -= 1
+
+        while fits(dpi + 1):
+            dpi += 1
+
+        return dpi
+
+    def user_space_to_normalized(self, x: float, y: float) -> Tuple[float, float]:
+        """
+        Maps a point in PDF user space to the normalized displayed page. Points outside of
+        the crop box map outside of [0, 1].
+        """
+        x0, y0, x1, y1 = self.cropbox
+
+        # Normalized coordinates on the unrotated page, origin top-left
+        x, y = (x - x0) / (x1 - x0), (y1 - y) / (y1 - y0)
+
+        if self.rotation == 90:
+            x, y = 1 - y, x
+        elif self.rotation == 180:
+            x, y = 1 - x, 1 - y
+        elif self.rotation == 270:
+            x, y = y, 1 - x
+
+        return x, y
+
+    def normalized_to_user_space(self, x: float, y: float) -> Tuple[float, float]:
+        """
+        Maps a point on the normalized displayed page to PDF user space
+        """
+        x0, y0, x1, y1 = self.cropbox
+
+        if self.rotation == 90:
+            x, y = y, 1 - x
+        elif self.rotation == 180:
+            x, y = 1 - x, 1 - y
+        elif self.rotation == 270:
+            x, y = 1 - y, x
+
+        return x0 + x * (x1 - x0), y1 - y * (y1 - y0)
+
+    def normalized_to_points(self, x: float, y: float) -> Tuple[float, float]:
+        return x * self.width, y * self.height
+
+    def points_to_normalized(self, x: float, y: float) -> Tuple[float, float]:
+        return x / self.width, y / self.height
+
+    def normalized_to_pixels(self, x: float, y: float, dpi: float) -> Tuple[float, float]:
+        width, height = self.get_render_size(dpi)
+
+        return x * width, y * height
+
+    def pixels_to_normalized(self, x: float, y: float, dpi: float) -> Tuple[float, float]:
+        width, height = self.get_render_size(dpi)
+
+        return x / width, y / height
+
+    def bbox_from_user_space(self, x0: float, y0: float, x1: float, y1: float, clamp: bool = True) -> NormBBox:
+        """
+        Converts a rectangle in user space, such as an annotation's /Rect, to a NormBBox
+        """
+        corners = [self.user_space_to_normalized(x, y) for x, y in ((x0, y0), (x0, y1), (x1, y0), (x1, y1))]
+        xs, ys = [x for x, _ in corners], [y for _, y in corners]
+
+        if clamp:
+            xs, ys = [_clamp(x) for x in xs], [_clamp(y) for y in ys]
+
+        return NormBBox(x0=min(xs), top=min(ys), x1=max(xs), bottom=max(ys))
+
+    def layout_to_user_space(self, x: float, y: float) -> Tuple[float, float]:
+        """
+        Maps a point in pdfminer's layout space (origin at the bottom-left) back to PDF user space,
+        undoing the initial transformation matrix pdfminer renders the page with
+        """
+        x0, y0, x1, y1 = self.mediabox
+
+        if self.rotation == 90:
+            return x1 - y, x + y0
+        elif self.rotation == 180:
+            return x1 - x, y1 - y
+        elif self.rotation == 270:
+            return x0 + y, y1 - x
+
+        return x0 + x, y0 + y
+
+    def bbox_from_pdfplumber(self, x0: float, top: float, x1: float, bottom: float, clamp: bool = True) -> NormBBox:
+        """
+        Converts a box reported by pdfplumber, such as a word's `x0`, `top`, `x1` and `bottom`, to a NormBBox
+        """
+        mx0, my0, mx1, my1 = self.mediabox
+        layout_height = (mx1 - mx0) if self.is_rotated_sideways else (my1 - my0)
+
+        corners = [
+            self.layout_to_user_space(x0, layout_height - bottom),
+            self.layout_to_user_space(x1, layout_height - top),
+        ]
+
+        return self.bbox_from_user_space(*corners[0], *corners[1], clamp=clamp)
+
+    def bbox_to_user_space(self, bbox: NormBBox) -> Box:
+        """
+        Converts a NormBBox to a rectangle (x0, y0, x1, y1) in user space
+        """
+        corners = [self.normalized_to_user_space(x, y) for x, y in ((bbox.x0, bbox.top), (bbox.x1, bbox.bottom))]
+        xs, ys = [x for x, _ in corners], [y for _, y in corners]
+
+        return min(xs), min(ys), max(xs), max(ys)
+
+    def bbox_to_pixels(self, bbox: NormBBox, dpi: float) -> Tuple[int, int, int, int]:
+        """
+        Converts a NormBBox to the pixel box (left, top, right, bottom) it covers in a raster at the given DPI
+        """
+        width, height = self.get_render_size(dpi)
+
+        return (
+            int(bbox.x0 * width),
+            int(bbox.top * height),
+            min(ceil(bbox.x1 * width), width),
+            min(ceil(bbox.bottom * height), height),
+        )
+
+    def bbox_from_pixels(self, left: float, top: float, right: float, bottom: float, dpi: float) -> NormBBox:
+        width, height = self.get_render_size(dpi)
+
+        return NormBBox(
+            x0=_clamp(left / width), top=_clamp(top / height), x1=_clamp(right / width), bottom=_clamp(bottom / height)
+        )
@@ -335,4 +335,14 @@ class GoogleDocumentAIProvider(BaseProvider):
         return self._gcp_documents_to_result(documents)
 
     def _call(self, document: Document, pages=...) -> ProviderResult:
-        return self._process_document_concurrent(document.get_bytes())
+        if pages is None or pages == list(range(1, document.num_pages + 1)):
+            return self._process_document_concurrent(document.get_bytes())
+
+        # Only send the requested pages, then map the results back to the original page numbers
+        subset = Document.from_pages([document.get_page(page_number) for page_number in pages])
+        result = self._process_document_concurrent(subset.get_bytes())
+
+        for page_result in result.page_results or []:
+            page_result.page_number = pages[page_result.page_number - 1]
+
+        return result
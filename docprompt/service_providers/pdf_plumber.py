@@ -0,0 +1,225 @@
+from io import BytesIO
+from statistics import median
+from typing import TYPE_CHECKING, List, Optional
+
+import pdfplumber
+
+from docprompt.schema.layout import BoundingPoly, Geometry, NormBBox, SegmentLevels, TextBlock, TextSpan
+from docprompt.schema.operations import PageResult, PageTextExtractionOutput
+from docprompt.schema.page_geometry import PageGeometry
+from docprompt.service_providers.types import OPERATIONS
+
+from .base import BaseProvider, ProviderResult
+
+if TYPE_CHECKING:
+    from pdfplumber.page import Page
+
+    from docprompt.schema.document import Document
+
+
+def _geometry_from_bbox(bbox: NormBBox) -> Geometry:
+    return Geometry(bounding_box=bbox, bounding_poly=BoundingPoly.from_norm_bbox(bbox))
+
+
+def _block_from_children(children: List[TextBlock], text: str, type: SegmentLevels, span: TextSpan) -> TextBlock:
+    bbox = NormBBox.combine(*[child.bounding_box for child in children])
+    directions = {child.direction for child in children}
+
+    return TextBlock(
+        text=text,
+        type=type,
+        geometry=_geometry_from_bbox(bbox),
+        direction=directions.pop() if len(directions) == 1 else None,
+        confidence=1.0,
+        text_spans=[span],
+    )
+
+
+class PdfPlumberProvider(BaseProvider):
+    """
+    Extracts words, lines and blocks from a PDF's own text layer using pdfplumber.
+
+    Born-digital PDFs already contain exact text, so this provider runs offline and
+    needs no OCR. Pages without a text layer (e.g. scans) produce empty results.
+    """
+
+    name = "PdfPlumberProvider"
+
+    def __init__(
+        self,
+        *,
+        x_tolerance: float = 3,
+        y_tolerance: float = 3,
+        keep_blank_chars: bool = False,
+        use_text_flow: bool = False,
+        block_gap_ratio: float = 1.0,
+    ):
+        """
+        `x_tolerance`, `y_tolerance`, `keep_blank_chars` and `use_text_flow` are passed
+        through to pdfplumber's word extraction. Consecutive lines are grouped into the
+        same block while the vertical gap between them is at most `block_gap_ratio`
+        times the median line height of the page.
+        """
+        self.x_tolerance = x_tolerance
+        self.y_tolerance = y_tolerance
+        self.keep_blank_chars = keep_blank_chars
+        self.use_text_flow = use_text_flow
+        self.block_gap_ratio = block_gap_ratio
+
+    @property
+    def capabilities(self) -> list[OPERATIONS]:
+        return [OPERATIONS.TEXT_EXTRACTION]
+
+    def _extract_words(self, page: "Page") -> List[TextBlock]:
+        words = page.extract_words(
+            x_tolerance=self.x_tolerance,
+            y_tolerance=self.y_tolerance,
+            keep_blank_chars=self.keep_blank_chars,
+            use_text_flow=self.use_text_flow,
+        )
+
+        # Normalizing against the crop box and rotation lines the words up with rasters of the page
+        geometry = PageGeometry.from_pdfplumber_page(page)
+        word_blocks = []
+
+        for word in words:
+            if not word["text"].strip():
+                continue
+
+            bbox = geometry.bbox_from_pdfplumber(word["x0"], word["top"], word["x1"], word["bottom"])
+
+            word_blocks.append(
+                TextBlock(
+                    text=word["text"],
+                    type="word",
+                    geometry=_geometry_from_bbox(bbox),
+                    direction="UP" if word.get("upright", True) else None,
+                    confidence=1.0,
+                )
+            )
+
+        return word_blocks
+
+    def _group_lines(self, words: List[TextBlock]) -> List[List[TextBlock]]:
+        """
+        Groups words into lines. A word joins the current line if its vertical center
+        falls within the line's vertical extent.
+        """
+        lines: List[List[TextBlock]] = []
+
+        if self.use_text_flow:
+            ordered = words
+        else:
+            ordered = sorted(words, key=lambda word: (word.bounding_box.top, word.bounding_box.x0))
+
+        line_top = line_bottom = 0.0
+
+        for word in ordered:
+            if lines and line_top <= word.bounding_box.y_center <= line_bottom:
+                lines[-1].append(word)
+                line_top = min(line_top, word.bounding_box.top)
+                line_bottom = max(line_bottom, word.bounding_box.bottom)
+            else:
+                lines.append([word])
+                line_top, line_bottom = word.bounding_box.top, word.bounding_box.bottom
+
+        if not self.use_text_flow:
+            for line in lines:
+                line.sort(key=lambda word: word.bounding_box.x0)
+
+        return lines
+
+    def _group_blocks(self, lines: List[List[TextBlock]]) -> List[List[List[TextBlock]]]:
+        """
+        Groups consecutive lines into blocks, splitting on large vertical gaps or when
+        lines do not overlap horizontally
+        """
+        if not lines:
+            return []
+
+        line_bboxes = [NormBBox.combine(*[word.bounding_box for word in line]) for line in lines]
+        max_gap = median(bbox.height for bbox in line_bboxes) * self.block_gap_ratio
+
+        blocks = [[lines[0]]]
+        block_bbox = line_bboxes[0]
+
+        for line, line_bbox in zip(lines[1:], line_bboxes[1:]):
+            gap = line_bbox.top - block_bbox.bottom
+            overlaps_horizontally = line_bbox.x0 < block_bbox.x1 and line_bbox.x1 > block_bbox.x0
+
+            if 0 <= gap <= max_gap and overlaps_horizontally:
+                blocks[-1].append(line)
+                block_bbox = block_bbox + line_bbox
+            else:
+                blocks.append([line])
+                block_bbox = line_bbox
+
+        return blocks
+
+    def extract_page(self, page: "Page") -> PageTextExtractionOutput:
+        """
+        Extracts the text layer of a single pdfplumber page
+        """
+        words = self._extract_words(page)
+        blocks = self._group_blocks(self._group_lines(words))
+
+        page_text = ""
+        word_blocks: List[TextBlock] = []
+        line_blocks: List[TextBlock] = []
+        block_blocks: List[TextBlock] = []
+
+        for block_index, block in enumerate(blocks):
+            if block_index > 0:
+                page_text += "\n"  # Blank line between blocks
+
+            block_start = len(page_text)
+            block_line_blocks = []
+
+            for line in block:
+                line_start = len(page_text)
+
+                for word_index, word in enumerate(line):
+                    if word_index > 0:
+                        page_text += " "
+
+                    word_start = len(page_text)
+                    page_text += word.text
+                    word.text_spans = [TextSpan(start_index=word_start, end_index=len(page_text), level="page")]
+                    word_blocks.append(word)
+
+                line_span = TextSpan(start_index=line_start, end_index=len(page_text), level="page")
+                line_block = _block_from_children(line, page_text[line_start : len(page_text)], "line", line_span)
+                block_line_blocks.append(line_block)
+
+                page_text += "\n"
+
+            block_end = len(page_text) - 1  # Exclude the trailing newline
+            block_span = TextSpan(start_index=block_start, end_index=block_end, level="page")
+            block_blocks.append(
+                _block_from_children(block_line_blocks, page_text[block_start:block_end], "block", block_span)
+            )
+            line_blocks.extend(block_line_blocks)
+
+        return PageTextExtractionOutput(
+            text=page_text,
+            words=word_blocks,
+            lines=line_blocks,
+            blocks=block_blocks,
+        )
+
+    def _call(self, document: "Document", pages: Optional[list[int]] = None) -> ProviderResult:
+        pages = pages or list(range(1, document.num_pages + 1))
+
+        page_results = []
+
+        with pdfplumber.open(BytesIO(document.file_bytes)) as pdf:
+            for page_number in pages:
+                page_results.append(
+                    PageResult(
+                        provider_name=self.name,
+                        page_number=page_number,
+                        ocr_result=self.extract_page(pdf.pages[page_number - 1]),
+                    )
+                )
+
+        return ProviderResult(provider_name=self.name, page_results=page_results)
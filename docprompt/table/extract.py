@@ -1,10 +1,9 @@
 import warnings
-from io import BytesIO
 from typing import Dict, List, Literal, NamedTuple, Optional, TypedDict
 
 from PIL import Image
 
-from docprompt.schema.document import Document
+from docprompt.schema.document import Document, DocumentPage
 from docprompt.schema.layout import TextBlock
 
 try:
@@ -203,27 +202,34 @@ def extract_tables_from_page(
     return extracted_tables
 
 
+def extract_tables_from_document_page(
+    page: DocumentPage,
+    table_detector=None,
+    table_layout_detector=None,
+) -> list[ExtractedTable]:
+    """
+    Extracts tables from a single page, using the page's word-level text data
+    """
+    if page.text_data is None:
+        warnings.warn(f"No text data found for page {page.page_number}, skipping...")
+        return []
+
+    return extract_tables_from_page(page.image, page.text_data.words, table_detector, table_layout_detector)
+
+
 def extract_tables_from_container(
     document: Document,
     pages: Optional[List[int]] = None,
     table_detector=None,
     table_layout_detector=None,
 ):
-    pages = pages or list(range(1, document.page_count))
+    pages = pages or list(range(1, document.page_count + 1))
 
     detected_tables = {}
 
     for page_number in pages:
-        if document.text_data[page_number] is None:
-            warnings.warn(f"No text data found for page {page_number}, skipping...")
-            detected_tables[page_number] = []
-            continue
-
-        word_blocks = document.text_data[page_number].words
-        rasterized_page = Image.open(BytesIO(document.rasterize_page(page_number)))
-
-        extracted_table = extract_tables_from_page(rasterized_page, word_blocks, table_detector, table_layout_detector)
-
-        detected_tables[page_number] = extracted_table
+        detected_tables[page_number] = extract_tables_from_document_page(
+            document.get_page(page_number), table_detector, table_layout_detector
+        )
 
     return detected_tables
@@ -1 +1,4 @@
+from .encryption import EncryptedDocumentError
+from .fingerprint import find_near_duplicates
+from .image import is_image
 from .util import get_page_count, is_pdf, load_document, load_document_from_url, load_documents_from_urls
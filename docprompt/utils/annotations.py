@@ -0,0 +1,392 @@
+from io import BytesIO
+from typing import TYPE_CHECKING, Dict, List, Literal, Optional, Tuple
+
+from pydantic import BaseModel, Field
+from pypdf import PdfReader, PdfWriter
+from pypdf.generic import (
+    ArrayObject,
+    DecodedStreamObject,
+    DictionaryObject,
+    FloatObject,
+    NameObject,
+    NumberObject,
+    TextStringObject,
+)
+
+from docprompt.schema.layout import BoundingPoly, NormBBox, Point, TextBlock
+from docprompt.schema.page_geometry import PageGeometry
+from docprompt.utils.util import format_number
+
+if TYPE_CHECKING:
+    from docprompt.schema.document import Document
+
+AnnotationType = Literal[
+    "highlight",
+    "underline",
+    "strikeout",
+    "squiggly",
+    "note",
+    "free_text",
+    "link",
+    "rectangle",
+    "circle",
+    "line",
+    "polygon",
+    "ink",
+    "stamp",
+    "other",
+]
+
+ANNOTATION_SUBTYPES: Dict[str, AnnotationType] = {
+    "/Highlight": "highlight",
+    "/Underline": "underline",
+    "/StrikeOut": "strikeout",
+    "/Squiggly": "squiggly",
+    "/Text": "note",
+    "/FreeText": "free_text",
+    "/Link": "link",
+    "/Square": "rectangle",
+    "/Circle": "circle",
+    "/Line": "line",
+    "/Polygon": "polygon",
+    "/PolyLine": "polygon",
+    "/Ink": "ink",
+    "/Stamp": "stamp",
+}
+
+# Form widgets are exposed as form fields, and popups only hold the open/closed state of their parent's note
+IGNORED_SUBTYPES = ("/Widget", "/Popup")
+
+Color = Tuple[float, float, float]
+
+DEFAULT_HIGHLIGHT_COLOR: Color = (1.0, 0.92, 0.23)
+DEFAULT_RECTANGLE_COLOR: Color = (1.0, 0.0, 0.0)
+
+# Annotation flags, from table 165 of the PDF 1.7 spec
+PRINT_FLAG = 1 << 2
+
+
+class Annotation(BaseModel):
+    """
+    Represents a single annotation on a page, such as a highlight, a note or a link
+    """
+
+    type: AnnotationType
+    subtype: str = Field(description="The raw PDF annotation subtype, e.g. '/Highlight'")
+    page_number: int = Field(description="The 1-indexed page the annotation is placed on")
+    bounding_box: NormBBox
+    bounding_polys: List[BoundingPoly] = Field(
+        default_factory=list,
+        description="The marked regions of text markup annotations, one per line of marked text",
+        repr=False,
+    )
+    author: Optional[str] = None
+    subject: Optional[str] = None
+    contents: Optional[str] = Field(default=None, description="The text of the annotation, e.g. a reviewer's comment")
+    color: Optional[Color] = Field(default=None, description="The RGB color of the annotation, each value in [0, 1]")
+    modified_at: Optional[str] = Field(default=None, description="The raw PDF date string of the last modification")
+    uri: Optional[str] = Field(default=None, description="The target of a link to an external resource")
+    destination_page: Optional[int] = Field(default=None, description="The 1-indexed target of an internal link")
+
+
+def _parse_color(value) -> Optional[Color]:
+    components = [float(v) for v in value or []]
+
+    if len(components) == 1:
+        return (components[0],) * 3
+    elif len(components) == 3:
+        return tuple(components)
+    elif len(components) == 4:
+        c, m, y, k = components
+        return ((1 - c) * (1 - k), (1 - m) * (1 - k), (1 - y) * (1 - k))
+
+    return None
+
+
+def _optional_text(annotation: DictionaryObject, key: str) -> Optional[str]:
+    return str(annotation[key]) if key in annotation else None
+
+
+def _to_normalized_points(geometry: PageGeometry, coordinates: List[float]) -> List[Point]:
+    points = []
+
+    for x, y in zip(coordinates[::2], coordinates[1::2]):
+        x, y = geometry.user_space_to_normalized(x, y)
+        points.append(Point(x=min(max(x, 0.0), 1.0), y=min(max(y, 0.0), 1.0)))
+
+    return points
+
+
+def _quad_points_to_polys(geometry: PageGeometry, quad_points: List[float]) -> List[BoundingPoly]:
+    polys = []
+
+    for index in range(0, len(quad_points) - 7, 8):
+        # Despite the spec, every major viewer writes quads as top-left, top-right, bottom-left, bottom-right
+        top_left, top_right, bottom_left, bottom_right = _to_normalized_points(geometry, quad_points[index : index + 8])
+        polys.append(BoundingPoly(normalized_vertices=[top_left, top_right, bottom_right, bottom_left]))
+
+    return polys
+
+
+def _resolve_destination_page(reader: PdfReader, destination) -> Optional[int]:
+    """
+    Resolves an explicit or named link destination to a 1-indexed page number
+    """
+    page_index = None
+
+    try:
+        if isinstance(destination, ArrayObject) and len(destination) > 0:
+            page_index = reader.get_page_number(destination[0].get_object())
+        else:
+            named_destination = reader.named_destinations.get(str(destination))
+
+            if named_destination is not None:
+                page_index = reader.get_destination_page_number(named_destination)
+    except Exception:
+        pass  # Broken destinations are common, and shouldn't prevent reading the rest
+
+    # pypdf returns -1 (or None, depending on the version) for pages that aren't in the document
+    if page_index is None or page_index < 0:
+        return None
+
+    return page_index + 1
+
+
+def _get_link_target(reader: PdfReader, annotation: DictionaryObject) -> Tuple[Optional[str], Optional[int]]:
+    if "/Dest" in annotation:
+        return None, _resolve_destination_page(reader, annotation["/Dest"])
+
+    action = annotation["/A"] if "/A" in annotation else None
+
+    if action is None:
+        return None, None
+
+    if action.get("/S") == "/URI" and "/URI" in action:
+        return str(action["/URI"]), None
+
+    if action.get("/S") == "/GoTo" and "/D" in action:
+        return None, _resolve_destination_page(reader, action["/D"])
+
+    return None, None
+
+
+def get_page_annotations(reader: PdfReader, page_number: int) -> List[Annotation]:
+    """
+    Returns the annotations placed on a single page (1-indexed), excluding form widgets
+    """
+    pdf_page = reader.pages[page_number - 1]
+    geometry = PageGeometry.from_pdf_page(pdf_page)
+    annotations = []
+
+    for annotation in pdf_page["/Annots"] if "/Annots" in pdf_page else []:
+        annotation = annotation.get_object()
+        subtype = str(annotation.get("/Subtype", ""))
+
+        if subtype in IGNORED_SUBTYPES or "/Rect" not in annotation:
+            continue
+
+        bounding_polys = []
+
+        if "/QuadPoints" in annotation:
+            bounding_polys = _quad_points_to_polys(geometry, [float(v) for v in annotation["/QuadPoints"]])
+
+        uri, destination_page = None, None
+
+        if subtype == "/Link":
+            uri, destination_page = _get_link_target(reader, annotation)
+
+        annotations.append(
+            Annotation(
+                type=ANNOTATION_SUBTYPES.get(subtype, "other"),
+                subtype=subtype,
+                page_number=page_number,
+                bounding_box=geometry.bbox_from_user_space(*(float(v) for v in annotation["/Rect"])),
+                bounding_polys=bounding_polys,
+                author=_optional_text(annotation, "/T"),
+                subject=_optional_text(annotation, "/Subj"),
+                contents=_optional_text(annotation, "/Contents"),
+                color=_parse_color(annotation["/C"] if "/C" in annotation else None),
+                modified_at=_optional_text(annotation, "/M"),
+                uri=uri,
+                destination_page=destination_page,
+            )
+        )
+
+    return annotations
+
+
+def get_annotations(file_bytes: bytes, pages: Optional[List[int]] = None) -> List[Annotation]:
+    """
+    Returns the annotations of a PDF, in page order
+    """
+    reader = PdfReader(BytesIO(file_bytes))
+    annotations = []
+
+    for page_number in range(1, len(reader.pages) + 1):
+        if pages is not None and page_number not in pages:
+            continue
+
+        annotations.extend(get_page_annotations(reader, page_number))
+
+    return annotations
+
+
+def _block_to_quad(geometry: PageGeometry, block: TextBlock) -> List[Tuple[float, float]]:
+    """
+    Returns the corners of a text block in user space, ordered top-left, top-right, bottom-left, bottom-right
+    """
+    if block.geometry.bounding_poly is not None and len(block.geometry.bounding_poly.normalized_vertices) == 4:
+        top_left, top_right, bottom_right, bottom_left = (
+            (v.x, v.y) for v in block.geometry.bounding_poly.normalized_vertices
+        )
+    else:
+        bbox = block.bounding_box
+        top_left, top_right = (bbox.x0, bbox.top), (bbox.x1, bbox.top)
+        bottom_right, bottom_left = (bbox.x1, bbox.bottom), (bbox.x0, bbox.bottom)
+
+    return [geometry.normalized_to_user_space(*point) for point in (top_left, top_right, bottom_left, bottom_right)]
+
+
+def _build_appearance(
+    rect: List[float], operations: List[str], resources: Optional[DictionaryObject] = None
+) -> DecodedStreamObject:
+    stream = DecodedStreamObject()
+    stream.set_data("\n".join(operations).encode("latin-1"))
+    stream.update(
+        {
+            NameObject("/Type"): NameObject("/XObject"),
+            NameObject("/Subtype"): NameObject("/Form"),
+            NameObject("/BBox"): ArrayObject([FloatObject(v) for v in rect]),
+            NameObject("/Resources"): resources or DictionaryObject(),
+        }
+    )
+
+    return stream
+
+
+def _build_annotation(
+    writer: PdfWriter,
+    geometry: PageGeometry,
+    block: TextBlock,
+    annotation_type: Literal["highlight", "rectangle"],
+    color: Color,
+    author: Optional[str],
+    contents: Optional[str],
+    line_width: float,
+) -> DictionaryObject:
+    quad = _block_to_quad(geometry, block)
+    xs, ys = [x for x, _ in quad], [y for _, y in quad]
+
+    padding = line_width / 2 if annotation_type == "rectangle" else 0
+    rect = [min(xs) - padding, min(ys) - padding, max(xs) + padding, max(ys) + padding]
+
+    top_left, top_right, bottom_left, bottom_right = quad
+    path = [top_left, top_right, bottom_right, bottom_left]
+    color_operands = " ".join(format_number(v) for v in color)
+
+    path_operations = [f"{format_number(path[0][0])} {format_number(path[0][1])} m"]
+    path_operations += [f"{format_number(x)} {format_number(y)} l" for x, y in path[1:]]
+
+    if annotation_type == "highlight":
+        # Multiply blending keeps the text under the highlight readable
+        resources = DictionaryObject(
+            {
+                NameObject("/ExtGState"): DictionaryObject(
+                    {NameObject("/GS0"): DictionaryObject({NameObject("/BM"): NameObject("/Multiply")})}
+                )
+            }
+        )
+        appearance = _build_appearance(
+            rect, ["q", "/GS0 gs", f"{color_operands} rg", *path_operations, "h f", "Q"], resources
+        )
+
+        annotation = DictionaryObject(
+            {
+                NameObject("/Subtype"): NameObject("/Highlight"),
+                NameObject("/QuadPoints"): ArrayObject([FloatObject(v) for point in quad for v in point]),
+            }
+        )
+    else:
+        appearance = _build_appearance(
+            rect, ["q", f"{format_number(line_width)} w", f"{color_operands} RG", *path_operations, "s", "Q"]
+        )
+
+        annotation = DictionaryObject(
+            {
+                NameObject("/Subtype"): NameObject("/Square"),
+                NameObject("/BS"): DictionaryObject({NameObject("/W"): FloatObject(line_width)}),
+            }
+        )
+
+    annotation.update(
+        {
+            NameObject("/Type"): NameObject("/Annot"),
+            NameObject("/Rect"): ArrayObject([FloatObject(v) for v in rect]),
+            NameObject("/C"): ArrayObject([FloatObject(v) for v in color]),
+            NameObject("/F"): NumberObject(PRINT_FLAG),
+            NameObject("/AP"): DictionaryObject({NameObject("/N"): writer.add_object(appearance)}),
+        }
+    )
+
+    if author is not None:
+        annotation[NameObject("/T")] = TextStringObject(author)
+
+    if contents is not None:
+        annotation[NameObject("/Contents")] = TextStringObject(contents)
+
+    return annotation
+
+
+def annotate_document(
+    document: "Document",
+    blocks: Dict[int, List[TextBlock]],
+    *,
+    annotation_type: Literal["highlight", "rectangle"] = "highlight",
+    color: Optional[Color] = None,
+    author: Optional[str] = None,
+    include_text: bool = False,
+    line_width: float = 1.5,
+) -> "Document":
+    """
+    Returns a new document with a highlight or rectangle annotation drawn around each of
+    the given text blocks (per page). The annotations follow the blocks' bounding polys
+    when they have them, so skewed text is marked accurately.
+
+    If `include_text` is set, each block's text is stored as the annotation's contents,
+    which most viewers show as a comment.
+    """
+    if color is None:
+        color = DEFAULT_HIGHLIGHT_COLOR if annotation_type == "highlight" else DEFAULT_RECTANGLE_COLOR
+
+    reader = PdfReader(BytesIO(document.file_bytes))
+    writer = PdfWriter(clone_from=reader)
+
+    for page_number, page_blocks in blocks.items():
+        if page_number < 1 or page_number > len(writer.pages):
+            raise ValueError(f"Page number must be between 1 and {len(writer.pages)}")
+
+        pdf_page = writer.pages[page_number - 1]
+        geometry = PageGeometry.from_pdf_page(pdf_page)
+
+        if "/Annots" not in pdf_page:
+            pdf_page[NameObject("/Annots")] = ArrayObject()
+
+        for block in page_blocks:
+            annotation = _build_annotation(
+                writer,
+                geometry,
+                block,
+                annotation_type,
+                color,
+                author,
+                block.text if include_text else None,
+                line_width,
+            )
+            annotation[NameObject("/P")] = pdf_page.indirect_reference
+
+            pdf_page["/Annots"].append(writer.add_object(annotation))
+
+    output_stream = BytesIO()
+    writer.write(output_stream)
+
+    return document._with_file_bytes(output_stream.getvalue())
@@ -0,0 +1,255 @@
+import json
+import zipfile
+from datetime import datetime
+from os import PathLike
+from typing import IO, TYPE_CHECKING, Dict, List, Optional, Union
+from urllib.parse import quote
+
+import fsspec
+from pydantic import BaseModel, Field
+
+from docprompt.rasterizers.base import get_device_format
+from docprompt.schema.operations import PageTextExtractionOutput
+from docprompt.utils.util import hash_from_bytes
+
+if TYPE_CHECKING:
+    from docprompt.schema.document import Document
+
+BUNDLE_FORMAT = "docprompt-bundle"
+BUNDLE_FORMAT_VERSION = 1
+
+MANIFEST_PATH = "manifest.json"
+DOCUMENT_PATH = "document.pdf"
+
+BundlePath = Union[PathLike, str]
+
+
+class InvalidBundleError(ValueError):
+    pass
+
+
+class BundleRaster(BaseModel):
+    page_number: int
+    dpi: int
+    device: str
+    rasterizer: str
+    path: str
+
+
+class BundleManifest(BaseModel):
+    """
+    Describes the contents of a document bundle
+    """
+
+    format: str = BUNDLE_FORMAT
+    version: int = BUNDLE_FORMAT_VERSION
+    created_at: datetime = Field(default_factory=datetime.now)
+
+    name: str
+    file_path: Optional[str] = None
+    source_mime_type: str = "application/pdf"
+    document_hash: str
+    page_count: int
+
+    sidecars: Dict[str, List[int]] = Field(
+        default_factory=dict, description="The pages with a text sidecar, keyed by provider name"
+    )
+    rasters: List[BundleRaster] = Field(default_factory=list)
+
+
+def sidecar_path(provider_name: str, page_number: int) -> str:
+    return f"sidecars/{quote(provider_name, safe='')}/{page_number}.json"
+
+
+def raster_path(page_number: int, dpi: int, device: str, rasterizer: str) -> str:
+    return f"rasters/{page_number}_{dpi}_{device}_{rasterizer}.{get_device_format(device)}"
+
+
+def save_bundle(
+    document: "Document",
+    fp: Union[BundlePath, IO[bytes]],
+    *,
+    include_rasters: bool = False,
+    raster_dpi: Optional[int] = None,
+    raster_device: str = "png16m",
+):
+    """
+    Saves a document and its text sidecars as a zip bundle, which can be written to any
+    path supported by fsspec.
+
+    The bundle holds the original PDF, a manifest and one JSON file per provider and page,
+    so that a single page's sidecar can be read without loading the rest. If
+    `include_rasters` is set, rasters already cached on the document's pages are stored
+    too, and if `raster_dpi` is given, every page is rasterized at that DPI and stored.
+    """
+    manifest = BundleManifest(
+        name=document.name,
+        file_path=document.file_path,
+        source_mime_type=document.source_mime_type,
+        document_hash=document.document_hash,
+        page_count=document.page_count,
+        sidecars={
+            provider_name: sorted(sidecars.keys()) for provider_name, sidecars in document.text_sidecars.items()
+        },
+    )
+
+    rasters: Dict[str, bytes] = {}
+
+    if include_rasters or raster_dpi is not None:
+        for page in document.pages:
+            if raster_dpi is not None:
+                page.rasterize(dpi=raster_dpi, device=raster_device)
+
+            for (dpi, device, rasterizer), raster in page._raster_cache.items():
+                path = raster_path(page.page_number, dpi, device, rasterizer)
+                rasters[path] = raster
+                manifest.rasters.append(
+                    BundleRaster(page_number=page.page_number, dpi=dpi, device=device, rasterizer=rasterizer, path=path)
+                )
+
+    def write(f: IO[bytes]):
+        with zipfile.ZipFile(f, "w", compression=zipfile.ZIP_DEFLATED) as bundle:
+            bundle.writestr(MANIFEST_PATH, manifest.model_dump_json(indent=2))
+            # PDFs are already compressed internally, so there's little to gain from deflating them again
+            bundle.writestr(DOCUMENT_PATH, document.file_bytes, compress_type=zipfile.ZIP_STORED)
+
+            for provider_name, sidecars in document.text_sidecars.items():
+                for page_number, page_text in sidecars.items():
+                    bundle.writestr(sidecar_path(provider_name, page_number), page_text.model_dump_json())
+
+            for path, raster in rasters.items():
+                bundle.writestr(path, raster, compress_type=zipfile.ZIP_STORED)
+
+    if isinstance(fp, (str, PathLike)):
+        with fsspec.open(str(fp), "wb") as f:
+            write(f)
+    else:
+        write(fp)
+
+
+class DocumentBundle:
+    """
+    Read access to a bundle written by `save_bundle`, loading members only when requested
+    """
+
+    def __init__(self, fp: Union[BundlePath, IO[bytes]]):
+        if isinstance(fp, (str, PathLike)):
+            self._file = fsspec.open(str(fp), "rb").open()
+            self._owns_file = True
+        else:
+            self._file = fp
+            self._owns_file = False
+
+        try:
+            self._zip = zipfile.ZipFile(self._file, "r")
+        except zipfile.BadZipFile as e:
+            self.close()
+            raise InvalidBundleError("File is not a document bundle") from e
+
+        try:
+            self.manifest = BundleManifest.model_validate(json.loads(self._zip.read(MANIFEST_PATH)))
+        except KeyError as e:
+            self.close()
+            raise InvalidBundleError("Bundle is missing its manifest") from e
+
+        if self.manifest.format != BUNDLE_FORMAT:
+            self.close()
+            raise InvalidBundleError(f"Unknown bundle format {self.manifest.format}")
+
+        if self.manifest.version > BUNDLE_FORMAT_VERSION:
+            self.close()
+            raise InvalidBundleError(
+                f"Bundle version {self.manifest.version} is newer than the supported version {BUNDLE_FORMAT_VERSION}"
+            )
+
+    def __enter__(self):
+        return self
+
+    def __exit__(self, *args):
+        self.close()
+
+    def close(self):
+        if getattr(self, "_zip", None) is not None:
+            self._zip.close()
+
+        if self._owns_file:
+            self._file.close()
+
+    def read_document_bytes(self, validate_hash: bool = True) -> bytes:
+        file_bytes = self._zip.read(DOCUMENT_PATH)
+
+        if validate_hash and hash_from_bytes(file_bytes) != self.manifest.document_hash:
+            raise InvalidBundleError("Document hash does not match the bundle manifest")
+
+        return file_bytes
+
+    def read_page_sidecar(
+        self, page_number: int, provider_name: Optional[str] = None
+    ) -> Optional[PageTextExtractionOutput]:
+        """
+        Reads the text sidecar of a single page, from the first provider if none is specified
+        """
+        if provider_name is None:
+            provider_name = next(iter(self.manifest.sidecars), None)
+
+            if provider_name is None:
+                return None
+
+        if page_number not in self.manifest.sidecars.get(provider_name, []):
+            return None
+
+        return PageTextExtractionOutput.model_validate_json(self._zip.read(sidecar_path(provider_name, page_number)))
+
+    def read_raster(
+        self, page_number: int, dpi: int, device: str = "png16m", rasterizer: str = "ghostscript"
+    ) -> Optional[bytes]:
+        key = (page_number, dpi, device, rasterizer)
+
+        for raster in self.manifest.rasters:
+            if (raster.page_number, raster.dpi, raster.device, raster.rasterizer) == key:
+                return self._zip.read(raster.path)
+
+        return None
+
+    def to_document(self, validate_hash: bool = True, load_rasters: bool = True) -> "Document":
+        from docprompt.schema.document import Document
+
+        text_sidecars = {
+            provider_name: {page_number: self.read_page_sidecar(page_number, provider_name) for page_number in pages}
+            for provider_name, pages in self.manifest.sidecars.items()
+        }
+
+        document = Document(
+            name=self.manifest.name,
+            file_path=self.manifest.file_path,
+            file_bytes=self.read_document_bytes(validate_hash=validate_hash),
+            source_mime_type=self.manifest.source_mime_type,
+            text_sidecars=text_sidecars,
+        )
+
+        if load_rasters:
+            for raster in self.manifest.rasters:
+                page = document.get_page(raster.page_number)
+                page._raster_cache[(raster.dpi, raster.device, raster.rasterizer)] = self._zip.read(raster.path)
+
+        return document
+
+
+def load_bundle(
+    fp: Union[BundlePath, IO[bytes]], *, validate_hash: bool = True, load_rasters: bool = True
+) -> "Document":
+    """
+    Loads a document, its text sidecars and any cached rasters from a bundle
+    """
+    with DocumentBundle(fp) as bundle:
+        return bundle.to_document(validate_hash=validate_hash, load_rasters=load_rasters)
+
+
+def load_bundle_page_sidecar(
+    fp: Union[BundlePath, IO[bytes]], page_number: int, provider_name: Optional[str] = None
+) -> Optional[PageTextExtractionOutput]:
+    """
+    Reads a single page's text sidecar from a bundle without loading the document or other pages
+    """
+    with DocumentBundle(fp) as bundle:
+        return bundle.read_page_sidecar(page_number, provider_name)
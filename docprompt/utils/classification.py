@@ -0,0 +1,170 @@
+import unicodedata
+from io import BytesIO
+from typing import TYPE_CHECKING, Dict, List, Literal, Optional
+
+import pdfplumber
+from pydantic import BaseModel, Field
+
+from docprompt.schema.page_geometry import PageGeometry
+
+if TYPE_CHECKING:
+    from pdfplumber.page import Page
+
+PageClassificationLabel = Literal[
+    "born_digital",  # A usable text layer, and the page is not dominated by an image
+    "scanned",  # An image-only page with no text layer
+    "scanned_with_text_layer",  # An image page with a plausible (usually OCR'd) hidden text layer
+    "scanned_with_bad_text_layer",  # An image page whose hidden text layer is sparse or garbled
+    "blank",  # Nothing on the page
+]
+
+# Pages whose images cover at least this fraction of the page are treated as scans
+SCANNED_IMAGE_COVERAGE = 0.5
+
+# Text layers with a lower fraction of readable characters than this are treated as bad
+MIN_TEXT_QUALITY = 0.8
+
+# Hidden OCR layers whose glyphs cover less than this fraction of the image area are treated as bad
+MIN_GLYPH_TO_IMAGE_RATIO = 0.01
+
+
+class PageMetrics(BaseModel):
+    """
+    The raw signals used to classify a page
+    """
+
+    char_count: int = Field(description="The number of non-whitespace characters in the text layer")
+    image_count: int = Field(description="The number of images placed on the page")
+    vector_count: int = Field(description="The number of lines, rects and curves drawn on the page")
+    image_coverage: float = Field(description="The fraction of the page covered by images, in [0, 1]")
+    glyph_coverage: float = Field(description="The fraction of the page covered by character boxes, in [0, 1]")
+    glyph_to_image_ratio: Optional[float] = Field(
+        default=None, description="Character box area divided by image area, if the page has images"
+    )
+    text_quality: float = Field(
+        description="The fraction of characters that are readable, i.e. not unmapped glyphs or control characters"
+    )
+
+
+class PageClassification(BaseModel):
+    """
+    Represents whether a page has a usable text layer or needs OCR
+    """
+
+    page_number: int
+    label: PageClassificationLabel
+    confidence: float = Field(description="A heuristic confidence score for the label, in [0.5, 1]")
+    metrics: PageMetrics = Field(repr=False)
+
+    @property
+    def needs_ocr(self) -> bool:
+        """
+        Whether the page's text should come from an OCR provider rather than the text layer
+        """
+        if self.label in ("scanned", "scanned_with_bad_text_layer"):
+            return True
+
+        # Born-digital pages can still have unusable text, e.g. fonts without a unicode mapping
+        return self.label == "born_digital" and self.metrics.text_quality < MIN_TEXT_QUALITY
+
+
+def _margin_confidence(value: float, threshold: float, scale: float) -> float:
+    """
+    Maps the distance of a value from a decision threshold to a confidence in [0.5, 1]
+    """
+    return 0.5 + 0.5 * min(1.0, abs(value - threshold) / scale)
+
+
+def _visible_area(obj: dict, geometry: PageGeometry) -> float:
+    """
+    Returns the fraction of the visible page an object covers, after clipping it to the crop box
+    """
+    return geometry.bbox_from_pdfplumber(obj["x0"], obj["top"], obj["x1"], obj["bottom"]).area
+
+
+def _is_readable_char(text: str) -> bool:
+    if text.startswith("(cid:") or text == "�":
+        return False
+
+    return all(unicodedata.category(c)[0] != "C" for c in text)
+
+
+def get_page_metrics(page: "Page") -> PageMetrics:
+    """
+    Computes the classification signals for a single pdfplumber page
+    """
+    geometry = PageGeometry.from_pdfplumber_page(page)
+
+    chars = [char for char in page.chars if char["text"].strip()]
+
+    # Areas are fractions of the visible page, so the page itself has an area of 1
+    image_area = sum(_visible_area(image, geometry) for image in page.images)
+    glyph_area = sum(_visible_area(char, geometry) for char in chars)
+
+    readable_count = sum(1 for char in chars if _is_readable_char(char["text"]))
+
+    return PageMetrics(
+        char_count=len(chars),
+        image_count=len(page.images),
+        vector_count=len(page.lines) + len(page.rects) + len(page.curves),
+        image_coverage=min(image_area, 1.0),
+        glyph_coverage=min(glyph_area, 1.0),
+        glyph_to_image_ratio=glyph_area / image_area if image_area else None,
+        text_quality=readable_count / len(chars) if chars else 0.0,
+    )
+
+
+def classify_page_metrics(page_number: int, metrics: PageMetrics) -> PageClassification:
+    """
+    Classifies a page from its metrics
+    """
+    coverage_confidence = _margin_confidence(metrics.image_coverage, SCANNED_IMAGE_COVERAGE, 0.4)
+    is_image_page = metrics.image_coverage >= SCANNED_IMAGE_COVERAGE
+
+    if metrics.char_count == 0:
+        if metrics.image_count == 0 and metrics.vector_count == 0:
+            label, confidence = "blank", 1.0
+        elif is_image_page:
+            label, confidence = "scanned", coverage_confidence
+        else:
+            # Small images or vector-only content (e.g. outlined text) and no text layer. This
+            # could just as well be a figure, so we're not very confident it's a scan
+            label, confidence = "scanned", 0.6
+    elif not is_image_page:
+        label = "born_digital"
+        confidence = min(coverage_confidence, _margin_confidence(metrics.text_quality, MIN_TEXT_QUALITY, 0.2))
+    else:
+        glyph_to_image_ratio = metrics.glyph_to_image_ratio or 0.0
+
+        quality_confidence = _margin_confidence(metrics.text_quality, MIN_TEXT_QUALITY, 0.2)
+        ratio_confidence = _margin_confidence(glyph_to_image_ratio, MIN_GLYPH_TO_IMAGE_RATIO, 0.05)
+
+        if metrics.text_quality < MIN_TEXT_QUALITY or glyph_to_image_ratio < MIN_GLYPH_TO_IMAGE_RATIO:
+            label = "scanned_with_bad_text_layer"
+            # Either signal is enough to call the layer bad
+            confidence = min(coverage_confidence, max(quality_confidence, ratio_confidence))
+        else:
+            label = "scanned_with_text_layer"
+            confidence = min(coverage_confidence, quality_confidence, ratio_confidence)
+
+    return PageClassification(page_number=page_number, label=label, confidence=confidence, metrics=metrics)
+
+
+def classify_pdf_pages(file_bytes: bytes, pages: Optional[List[int]] = None) -> Dict[int, PageClassification]:
+    """
+    Classifies each page of a PDF as born-digital, scanned, scanned with a good or bad
+    text layer, or blank. Page numbers are 1-indexed.
+    """
+    classifications = {}
+
+    with pdfplumber.open(BytesIO(file_bytes)) as pdf:
+        pages = pages or list(range(1, len(pdf.pages) + 1))
+
+        for page_number in pages:
+            if page_number < 1 or page_number > len(pdf.pages):
+                raise ValueError(f"Page number must be between 1 and {len(pdf.pages)}")
+
+            metrics = get_page_metrics(pdf.pages[page_number - 1])
+            classifications[page_number] = classify_page_metrics(page_number, metrics)
+
+    return classifications
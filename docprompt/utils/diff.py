@@ -0,0 +1,365 @@
+from collections import defaultdict
+from difflib import SequenceMatcher
+from typing import TYPE_CHECKING, Dict, List, Literal, Optional, Tuple
+
+from PIL import Image, ImageDraw
+from pydantic import BaseModel, Field
+
+from docprompt.schema.layout import BoundingPoly, Geometry, NormBBox, TextBlock
+from docprompt.utils.annotations import Color, annotate_document
+
+if TYPE_CHECKING:
+    from docprompt.schema.document import Document
+
+DiffChangeType = Literal["insertion", "deletion", "move"]
+DiffSide = Literal["a", "b"]
+
+# Pages with less word overlap than this are never aligned with each other
+MIN_PAGE_SIMILARITY = 0.3
+
+# Runs of fewer words than this are too common to be reliably reported as moves
+MIN_MOVE_WORDS = 3
+
+REDLINE_COLORS: Dict[DiffChangeType, Color] = {
+    "insertion": (0.0, 0.8, 0.0),
+    "deletion": (1.0, 0.0, 0.0),
+    "move": (0.0, 0.4, 1.0),
+}
+
+REDLINE_DPI = 100
+
+
+class PageAlignment(BaseModel):
+    """
+    A page of one document and its counterpart in the other, if there is one
+    """
+
+    page_number_a: Optional[int] = None
+    page_number_b: Optional[int] = None
+    similarity: float = Field(default=0.0, description="The Jaccard similarity of the two pages' words")
+
+
+class DiffRegion(BaseModel):
+    """
+    The location of changed words on a single line of a page
+    """
+
+    page_number: int
+    bounding_box: NormBBox
+
+
+class DiffChange(BaseModel):
+    """
+    A run of consecutive words that were inserted, deleted or moved. Deletions only have
+    regions in document A, insertions only in document B, and moves in both.
+    """
+
+    type: DiffChangeType
+    text: str
+    regions_a: List[DiffRegion] = Field(default_factory=list)
+    regions_b: List[DiffRegion] = Field(default_factory=list)
+
+    def get_regions(self, side: DiffSide) -> List[DiffRegion]:
+        return self.regions_a if side == "a" else self.regions_b
+
+
+class DocumentDiff(BaseModel):
+    page_alignment: List[PageAlignment]
+    changes: List[DiffChange]
+    similarity: float = Field(description="The fraction of words the two documents have in common, in [0, 1]")
+
+    @property
+    def insertions(self) -> List[DiffChange]:
+        return [change for change in self.changes if change.type == "insertion"]
+
+    @property
+    def deletions(self) -> List[DiffChange]:
+        return [change for change in self.changes if change.type == "deletion"]
+
+    @property
+    def moves(self) -> List[DiffChange]:
+        return [change for change in self.changes if change.type == "move"]
+
+    @property
+    def has_changes(self) -> bool:
+        return len(self.changes) > 0
+
+
+# A word of a document, along with the page it is on
+_Word = Tuple[int, TextBlock]
+
+
+def _get_page_words(document: "Document", provider_name: Optional[str]) -> Dict[int, List[TextBlock]]:
+    if not document.text_sidecars:
+        raise ValueError(f"Document {document} does not have text data. Try running `perform_text_extraction` first")
+
+    if provider_name is not None and provider_name not in document.text_sidecars:
+        raise ValueError(f"Document {document} does not have text data for provider {provider_name}")
+
+    return {
+        page.page_number: [word for word in page.get_text_blocks("word", provider_name) if word.text.strip()]
+        for page in document.pages
+    }
+
+
+def _normalize_word(text: str, ignore_case: bool) -> str:
+    text = text.strip()
+
+    return text.lower() if ignore_case else text
+
+
+def _jaccard(a: set, b: set) -> float:
+    if not a and not b:
+        return 1.0
+
+    return len(a & b) / len(a | b)
+
+
+def align_pages(
+    words_a: Dict[int, List[str]], words_b: Dict[int, List[str]], min_similarity: float = MIN_PAGE_SIMILARITY
+) -> List[PageAlignment]:
+    """
+    Aligns the pages of two documents in order, allowing for inserted and deleted pages,
+    by maximizing the total word similarity of the aligned pairs
+    """
+    pages_a, pages_b = sorted(words_a), sorted(words_b)
+    sets_a = [set(words_a[page_number]) for page_number in pages_a]
+    sets_b = [set(words_b[page_number]) for page_number in pages_b]
+
+    similarity = [[_jaccard(set_a, set_b) for set_b in sets_b] for set_a in sets_a]
+
+    # scores[i][j] is the best total similarity aligning the first i pages of A with the first j of B
+    scores = [[0.0] * (len(pages_b) + 1) for _ in range(len(pages_a) + 1)]
+
+    for i in range(1, len(pages_a) + 1):
+        for j in range(1, len(pages_b) + 1):
+            scores[i][j] = max(scores[i - 1][j], scores[i][j - 1])
+
+            if similarity[i - 1][j - 1] >= min_similarity:
+                scores[i][j] = max(scores[i][j], scores[i - 1][j - 1] + similarity[i - 1][j - 1])
+
+    alignment = []
+    i, j = len(pages_a), len(pages_b)
+
+    while i > 0 or j > 0:
+        if (
+            i > 0
+            and j > 0
+            and similarity[i - 1][j - 1] >= min_similarity
+            and scores[i][j] == scores[i - 1][j - 1] + similarity[i - 1][j - 1]
+        ):
+            alignment.append(
+                PageAlignment(
+                    page_number_a=pages_a[i - 1], page_number_b=pages_b[j - 1], similarity=similarity[i - 1][j - 1]
+                )
+            )
+            i, j = i - 1, j - 1
+        elif i > 0 and (j == 0 or scores[i][j] == scores[i - 1][j]):
+            alignment.append(PageAlignment(page_number_a=pages_a[i - 1]))
+            i -= 1
+        else:
+            alignment.append(PageAlignment(page_number_b=pages_b[j - 1]))
+            j -= 1
+
+    return list(reversed(alignment))
+
+
+def _on_same_line(a: NormBBox, b: NormBBox) -> bool:
+    overlap = min(a.bottom, b.bottom) - max(a.top, b.top)
+
+    return overlap >= 0.5 * min(a.height, b.height) and b.x0 >= a.x0
+
+
+def _to_regions(words: List[_Word]) -> List[DiffRegion]:
+    """
+    Returns the regions covered by a run of words, merging consecutive words on the same line
+    """
+    regions: List[DiffRegion] = []
+
+    for page_number, word in words:
+        previous = regions[-1] if regions else None
+
+        if (
+            previous is not None
+            and previous.page_number == page_number
+            and _on_same_line(previous.bounding_box, word.bounding_box)
+        ):
+            previous.bounding_box = previous.bounding_box + word.bounding_box
+        else:
+            regions.append(DiffRegion(page_number=page_number, bounding_box=word.bounding_box))
+
+    return regions
+
+
+def _detect_moves(changes: List[DiffChange], ignore_case: bool, min_move_words: int) -> List[DiffChange]:
+    """
+    Pairs up deletions and insertions of the same run of words, and reports them as moves
+    """
+    insertions_by_text = defaultdict(list)
+
+    for index, change in enumerate(changes):
+        if change.type == "insertion" and len(change.text.split()) >= min_move_words:
+            insertions_by_text[_normalize_word(change.text, ignore_case)].append(index)
+
+    moves: Dict[int, int] = {}  # Deletion index -> insertion index
+
+    for index, change in enumerate(changes):
+        candidates = insertions_by_text.get(_normalize_word(change.text, ignore_case))
+
+        if change.type == "deletion" and candidates:
+            moves[index] = candidates.pop(0)
+
+    moved_insertions = set(moves.values())
+    result = []
+
+    for index, change in enumerate(changes):
+        if index in moved_insertions:
+            continue
+
+        if index in moves:
+            change = DiffChange(
+                type="move", text=change.text, regions_a=change.regions_a, regions_b=changes[moves[index]].regions_b
+            )
+
+        result.append(change)
+
+    return result
+
+
+def diff_documents(
+    a: "Document",
+    b: "Document",
+    *,
+    provider_name: Optional[str] = None,
+    ignore_case: bool = False,
+    detect_moves: bool = True,
+    min_move_words: int = MIN_MOVE_WORDS,
+) -> DocumentDiff:
+    """
+    Compares two revisions of a document using the word-level text blocks of their sidecars.
+
+    Pages are aligned first, so that inserted or removed pages are reported as such. Words
+    are then diffed across the whole document in reading order, so text that reflows onto
+    another page isn't reported as changed. Deleted runs that reappear elsewhere are
+    reported as moves.
+    """
+    page_words_a = _get_page_words(a, provider_name)
+    page_words_b = _get_page_words(b, provider_name)
+
+    page_alignment = align_pages(
+        {n: [_normalize_word(w.text, ignore_case) for w in words] for n, words in page_words_a.items()},
+        {n: [_normalize_word(w.text, ignore_case) for w in words] for n, words in page_words_b.items()},
+    )
+
+    words_a: List[_Word] = [(n, word) for n in sorted(page_words_a) for word in page_words_a[n]]
+    words_b: List[_Word] = [(n, word) for n in sorted(page_words_b) for word in page_words_b[n]]
+
+    # autojunk would ignore common words like "the" on long documents, which breaks the alignment of legal text
+    matcher = SequenceMatcher(
+        a=[_normalize_word(word.text, ignore_case) for _, word in words_a],
+        b=[_normalize_word(word.text, ignore_case) for _, word in words_b],
+        autojunk=False,
+    )
+
+    changes = []
+
+    for tag, a_start, a_end, b_start, b_end in matcher.get_opcodes():
+        if tag in ("delete", "replace"):
+            deleted = words_a[a_start:a_end]
+            changes.append(
+                DiffChange(
+                    type="deletion",
+                    text=" ".join(word.text.strip() for _, word in deleted),
+                    regions_a=_to_regions(deleted),
+                )
+            )
+
+        if tag in ("insert", "replace"):
+            inserted = words_b[b_start:b_end]
+            changes.append(
+                DiffChange(
+                    type="insertion",
+                    text=" ".join(word.text.strip() for _, word in inserted),
+                    regions_b=_to_regions(inserted),
+                )
+            )
+
+    if detect_moves:
+        changes = _detect_moves(changes, ignore_case, min_move_words)
+
+    return DocumentDiff(page_alignment=page_alignment, changes=changes, similarity=matcher.ratio())
+
+
+def _get_side_changes(diff: DocumentDiff, side: DiffSide) -> List[DiffChange]:
+    return [change for change in diff.changes if change.get_regions(side)]
+
+
+def render_redline_image(
+    document: "Document", diff: DocumentDiff, page_number: int, side: DiffSide, *, dpi: int = REDLINE_DPI
+) -> Image.Image:
+    """
+    Rasterizes a page of one side of a diff with its changes highlighted: deletions in red
+    on side A, insertions in green on side B and moves in blue on both
+    """
+    image = document.get_page(page_number).get_image(dpi=dpi, device="png16m").convert("RGBA")
+    width, height = image.size
+
+    overlay = Image.new("RGBA", image.size, (0, 0, 0, 0))
+    draw = ImageDraw.Draw(overlay)
+
+    for change in _get_side_changes(diff, side):
+        color = tuple(round(v * 255) for v in REDLINE_COLORS[change.type])
+
+        for region in change.get_regions(side):
+            if region.page_number != page_number:
+                continue
+
+            bbox = region.bounding_box
+            draw.rectangle(
+                (bbox.x0 * width, bbox.top * height, bbox.x1 * width, bbox.bottom * height),
+                fill=color + (80,),
+                outline=color + (255,),
+            )
+
+    return Image.alpha_composite(image, overlay).convert("RGB")
+
+
+def _describe_change(change: DiffChange, side: DiffSide) -> str:
+    if change.type == "move":
+        other_pages = sorted({region.page_number for region in change.get_regions("b" if side == "a" else "a")})
+        direction = "to" if side == "a" else "from"
+
+        return f"Moved {direction} page {', '.join(str(page) for page in other_pages)}: {change.text}"
+
+    return f"{'Inserted' if change.type == 'insertion' else 'Deleted'}: {change.text}"
+
+
+def render_redline_pdf(document: "Document", diff: DocumentDiff, side: DiffSide, **kwargs) -> "Document":
+    """
+    Returns a copy of one side of a diff with its changes marked as highlight annotations,
+    colored like `render_redline_image`. Each change is described in the annotation's
+    comment, so the text of the PDF stays intact and the changes can be reviewed in any viewer.
+    """
+    blocks_by_type: Dict[DiffChangeType, Dict[int, List[TextBlock]]] = defaultdict(lambda: defaultdict(list))
+
+    for change in _get_side_changes(diff, side):
+        description = _describe_change(change, side)
+
+        for region in change.get_regions(side):
+            blocks_by_type[change.type][region.page_number].append(
+                TextBlock(
+                    text=description,
+                    type="word",
+                    geometry=Geometry(
+                        bounding_box=region.bounding_box,
+                        bounding_poly=BoundingPoly.from_norm_bbox(region.bounding_box),
+                    ),
+                )
+            )
+
+    for change_type, blocks in blocks_by_type.items():
+        document = annotate_document(
+            document, dict(blocks), color=REDLINE_COLORS[change_type], include_text=True, **kwargs
+        )
+
+    return document
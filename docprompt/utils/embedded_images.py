@@ -0,0 +1,146 @@
+from io import BytesIO
+from typing import Dict, List, Optional, Tuple
+
+import pdfplumber
+from PIL import Image
+from pydantic import BaseModel, Field, PrivateAttr
+from pypdf import PageObject, PdfReader
+from pypdf.generic import ArrayObject, NameObject
+
+from docprompt.schema.layout import NormBBox
+from docprompt.schema.page_geometry import PageGeometry
+
+
+class EmbeddedImage(BaseModel):
+    """
+    Represents an image XObject drawn on a page, at its native resolution
+    """
+
+    name: str = Field(description="The name of the image in the page's resources, e.g. 'Im0'")
+    page_number: int = Field(description="The 1-indexed page the image is drawn on")
+    bounding_box: NormBBox = Field(description="Where the image is drawn on the page")
+    width: int = Field(description="The native width of the image in pixels")
+    height: int = Field(description="The native height of the image in pixels")
+    dpi: Tuple[float, float] = Field(description="The effective horizontal and vertical resolution as drawn")
+    color_space: Optional[str] = Field(default=None, description="The PDF color space, e.g. 'DeviceRGB' or 'ICCBased'")
+    bits_per_component: Optional[int] = None
+    is_mask: bool = Field(default=False, description="Whether the image is a stencil mask")
+
+    _image: Optional[Image.Image] = PrivateAttr(default=None)
+
+    @property
+    def image(self) -> Optional[Image.Image]:
+        """
+        The decoded image, or None if its encoding isn't supported
+        """
+        return self._image
+
+
+def _describe_color_space(color_space) -> Optional[str]:
+    if color_space is None:
+        return None
+
+    color_space = color_space.get_object()
+
+    if isinstance(color_space, ArrayObject) and len(color_space) > 0:
+        color_space = color_space[0]
+
+    if isinstance(color_space, NameObject):
+        return str(color_space)[1:]
+
+    return None
+
+
+def _decode_page_images(pdf_page: PageObject) -> Dict[str, Tuple[Optional[Image.Image], Dict]]:
+    """
+    Decodes the images of a page with pypdf, keyed by resource name. Images nested inside
+    form XObjects are keyed by their own name, with top-level images taking precedence.
+    """
+    decoded = {}
+
+    for key in pdf_page.images.keys():
+        nested = not isinstance(key, str)
+        name = (key[-1] if nested else key).lstrip("/")
+
+        if name in decoded and nested:
+            continue
+
+        try:
+            image_file = pdf_page.images[key]
+        except Exception:
+            continue  # Unsupported filters or broken streams, which we can still report without pixels
+
+        x_object = {}
+
+        if image_file.indirect_reference is not None:
+            x_object = image_file.indirect_reference.get_object()
+
+        decoded[name] = (image_file.image, x_object)
+
+    return decoded
+
+
+def extract_page_images(
+    file_bytes: bytes, page_number: int, *, min_size: int = 0, include_masks: bool = False
+) -> List[EmbeddedImage]:
+    """
+    Returns the images drawn on a page (1-indexed), in drawing order.
+
+    Placement comes from pdfplumber and pixels from pypdf, so an image drawn several
+    times appears once per placement. Images smaller than `min_size` pixels on either
+    side, which are usually decorations, can be skipped.
+    """
+    reader = PdfReader(BytesIO(file_bytes))
+
+    if page_number < 1 or page_number > len(reader.pages):
+        raise ValueError(f"Page number must be between 1 and {len(reader.pages)}")
+
+    pdf_page = reader.pages[page_number - 1]
+    geometry = PageGeometry.from_pdf_page(pdf_page)
+    decoded = _decode_page_images(pdf_page)
+
+    images = []
+
+    with pdfplumber.open(BytesIO(file_bytes)) as pdf:
+        page = pdf.pages[page_number - 1]
+
+        for placement in page.images:
+            name = str(placement.get("name", ""))
+            is_mask = bool(placement.get("imagemask"))
+
+            if is_mask and not include_masks:
+                continue
+
+            image, x_object = decoded.get(name, (None, {}))
+
+            width, height = (int(v) for v in placement["srcsize"])
+
+            if min(width, height) < min_size:
+                continue
+
+            # pdfminer's layout space is rotated with the page, so the image's own axes are measured in user space
+            (ux0, uy0), (ux1, uy1) = (
+                geometry.layout_to_user_space(placement["x0"], placement["y0"]),
+                geometry.layout_to_user_space(placement["x1"], placement["y1"]),
+            )
+            placed_width = abs(ux1 - ux0) * geometry.user_unit / 72
+            placed_height = abs(uy1 - uy0) * geometry.user_unit / 72
+
+            embedded_image = EmbeddedImage(
+                name=name,
+                page_number=page_number,
+                bounding_box=geometry.bbox_from_pdfplumber(
+                    placement["x0"], placement["top"], placement["x1"], placement["bottom"]
+                ),
+                width=width,
+                height=height,
+                dpi=(width / placed_width if placed_width else 0.0, height / placed_height if placed_height else 0.0),
+                color_space=_describe_color_space(x_object.get("/ColorSpace")) or (image.mode if image else None),
+                bits_per_component=placement.get("bits"),
+                is_mask=is_mask,
+            )
+            embedded_image._image = image
+
+            images.append(embedded_image)
+
+    return images
@@ -0,0 +1,75 @@
+from io import BytesIO
+from typing import Optional
+
+from pypdf import PasswordType, PdfReader, PdfWriter
+from pypdf.errors import DependencyError, PdfReadError
+
+
+class EncryptedDocumentError(ValueError):
+    """
+    Raised when an encrypted PDF can't be opened with any of the available passwords
+    """
+
+    pass
+
+
+def _may_be_encrypted(file_bytes: bytes) -> bool:
+    """
+    Encryption is declared by an /Encrypt entry in the trailer, which is never compressed, so
+    a PDF without one anywhere in its bytes can't be encrypted and doesn't need to be parsed
+    """
+    return b"/Encrypt" in file_bytes
+
+
+def is_encrypted_pdf(file_bytes: bytes) -> bool:
+    """
+    Determines if a PDF is encrypted, without attempting to decrypt it
+    """
+    if not _may_be_encrypted(file_bytes):
+        return False
+
+    return PdfReader(BytesIO(file_bytes)).is_encrypted
+
+
+def decrypt_pdf_bytes(file_bytes: bytes, password: Optional[str] = None) -> bytes:
+    """
+    Returns the bytes of a decrypted copy of a PDF. Unencrypted PDFs are returned as-is.
+
+    The empty password is always tried as well, since many PDFs only have an owner
+    password that restricts printing or copying, and can be opened by anyone.
+    """
+    if not _may_be_encrypted(file_bytes):
+        return file_bytes
+
+    reader = PdfReader(BytesIO(file_bytes))
+
+    if not reader.is_encrypted:
+        return file_bytes
+
+    candidates = [password, ""] if password else [""]
+
+    for candidate in candidates:
+        try:
+            result = reader.decrypt(candidate)
+        except DependencyError as e:
+            raise EncryptedDocumentError(
+                "Document uses an encryption algorithm that requires the 'cryptography' package"
+            ) from e
+        except (NotImplementedError, PdfReadError) as e:
+            raise EncryptedDocumentError(f"Document uses an unsupported encryption scheme: {e}") from e
+
+        if result != PasswordType.NOT_DECRYPTED:
+            break
+    else:
+        if password:
+            raise EncryptedDocumentError("Document is encrypted and the given password is incorrect")
+
+        raise EncryptedDocumentError("Document is encrypted. Please provide a password")
+
+    # Cloning keeps the document catalog (forms, outlines, page labels, etc.) intact
+    writer = PdfWriter(clone_from=reader)
+
+    output_stream = BytesIO()
+    writer.write(output_stream)
+
+    return output_stream.getvalue()
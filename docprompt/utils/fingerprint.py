@@ -0,0 +1,306 @@
+import hashlib
+import random
+import re
+import unicodedata
+from collections import defaultdict
+from io import BytesIO
+from typing import TYPE_CHECKING, Dict, Iterable, List, Optional, Set, Tuple, Union
+
+from PIL import Image, ImageStat
+from pydantic import BaseModel, Field
+
+if TYPE_CHECKING:
+    from docprompt.schema.document import Document, DocumentPage
+    from docprompt.schema.operations import PageTextExtractionOutput
+
+# Rasters for hashing only need to capture the layout of a page, so a low resolution is plenty
+FINGERPRINT_DPI = 36
+
+IMAGE_HASH_SIZE = 8  # An 8x8 difference hash, i.e. 64 bits
+IMAGE_HASH_BITS = IMAGE_HASH_SIZE * IMAGE_HASH_SIZE
+
+# Pages whose grayscale raster varies less than this are treated as blank and never matched
+BLANK_PAGE_STDDEV = 2.0
+
+SHINGLE_SIZE = 5  # Words per shingle
+MINHASH_PERMUTATIONS = 64
+MINHASH_BANDS = 32  # Each band holds MINHASH_PERMUTATIONS / MINHASH_BANDS values
+
+_MERSENNE_PRIME = (1 << 61) - 1
+_permutation_rng = random.Random(0)  # Fixed, so that fingerprints are comparable across processes
+MINHASH_COEFFICIENTS = [
+    (_permutation_rng.randrange(1, _MERSENNE_PRIME), _permutation_rng.randrange(0, _MERSENNE_PRIME))
+    for _ in range(MINHASH_PERMUTATIONS)
+]
+
+
+class PageFingerprint(BaseModel):
+    """
+    Similarity-preserving hashes of a page's appearance and text
+    """
+
+    page_number: int
+    image_hash: str = Field(description="A 64 bit difference hash of the page raster, as hex")
+    text_hash: Optional[List[int]] = Field(
+        default=None,
+        description="A MinHash signature of the word shingles of the page's text, if it has text",
+        repr=False,
+    )
+    is_blank: bool = False
+
+
+class DocumentFingerprint(BaseModel):
+    name: str
+    document_hash: str
+    pages: List[PageFingerprint]
+
+
+class NearDuplicates(BaseModel):
+    """
+    Groups of similar documents and pages. Documents are referred to by their index in the
+    input, and pages by (document index, page number).
+    """
+
+    document_groups: List[List[int]] = Field(default_factory=list)
+    page_groups: List[List[Tuple[int, int]]] = Field(default_factory=list)
+
+
+def compute_image_hash(image: Image.Image) -> str:
+    """
+    Computes a difference hash, which is stable under re-encoding, rescaling and mild noise
+    """
+    small = image.convert("L").resize((IMAGE_HASH_SIZE + 1, IMAGE_HASH_SIZE), Image.LANCZOS)
+    pixels = list(small.getdata())
+
+    value = 0
+
+    for row in range(IMAGE_HASH_SIZE):
+        for col in range(IMAGE_HASH_SIZE):
+            left = pixels[row * (IMAGE_HASH_SIZE + 1) + col]
+            right = pixels[row * (IMAGE_HASH_SIZE + 1) + col + 1]
+            value = (value << 1) | int(left > right)
+
+    return f"{value:016x}"
+
+
+def normalize_text(text: str) -> List[str]:
+    """
+    Returns the words of a text, lowercased and without punctuation, so that OCR and text
+    layer output of the same page produce the same shingles
+    """
+    text = unicodedata.normalize("NFKC", text).lower()
+
+    return re.findall(r"\w+", text)
+
+
+def get_shingles(words: List[str], size: int = SHINGLE_SIZE) -> Set[int]:
+    if len(words) < size:
+        shingles = [" ".join(words)] if words else []
+    else:
+        shingles = [" ".join(words[i : i + size]) for i in range(len(words) - size + 1)]
+
+    return {int.from_bytes(hashlib.blake2b(s.encode("utf-8"), digest_size=8).digest(), "big") for s in shingles}
+
+
+def compute_text_hash(text: str) -> Optional[List[int]]:
+    """
+    Computes a MinHash signature of a text's word shingles, or None if there is no text
+    """
+    shingles = get_shingles(normalize_text(text))
+
+    if not shingles:
+        return None
+
+    return [min((a * shingle + b) % _MERSENNE_PRIME for shingle in shingles) for a, b in MINHASH_COEFFICIENTS]
+
+
+def _fingerprint_raster(
+    page_number: int, image: Image.Image, text_data: Optional["PageTextExtractionOutput"]
+) -> PageFingerprint:
+    text_hash = compute_text_hash(text_data.text) if text_data is not None else None
+
+    return PageFingerprint(
+        page_number=page_number,
+        image_hash=compute_image_hash(image),
+        text_hash=text_hash,
+        is_blank=text_hash is None and ImageStat.Stat(image.convert("L")).stddev[0] < BLANK_PAGE_STDDEV,
+    )
+
+
+def fingerprint_page(page: "DocumentPage", provider_name: Optional[str] = None) -> PageFingerprint:
+    """
+    Fingerprints a page from a low resolution raster and, if the page has a text sidecar, its text
+    """
+    image = page.get_image(dpi=FINGERPRINT_DPI, device="pnggray")
+
+    return _fingerprint_raster(page.page_number, image, page.get_text_data(provider_name))
+
+
+def fingerprint_document(document: "Document", provider_name: Optional[str] = None) -> DocumentFingerprint:
+    # One rasterizer run for the whole document, rather than one per page
+    rasters = document.rasterize_pdf(dpi=FINGERPRINT_DPI, device="pnggray")
+
+    return DocumentFingerprint(
+        name=document.name,
+        document_hash=document.document_hash,
+        pages=[
+            _fingerprint_raster(
+                page.page_number, Image.open(BytesIO(rasters[page.page_number])), page.get_text_data(provider_name)
+            )
+            for page in document.pages
+        ],
+    )
+
+
+def image_similarity(a: PageFingerprint, b: PageFingerprint) -> float:
+    distance = bin(int(a.image_hash, 16) ^ int(b.image_hash, 16)).count("1")
+
+    return 1 - distance / IMAGE_HASH_BITS
+
+
+def text_similarity(a: PageFingerprint, b: PageFingerprint) -> Optional[float]:
+    """
+    Estimates the Jaccard similarity of two pages' shingles, or None if either has no text
+    """
+    if a.text_hash is None or b.text_hash is None:
+        return None
+
+    return sum(1 for x, y in zip(a.text_hash, b.text_hash) if x == y) / MINHASH_PERMUTATIONS
+
+
+class _UnionFind:
+    def __init__(self):
+        self.parents = {}
+
+    def find(self, item):
+        self.parents.setdefault(item, item)
+
+        while self.parents[item] != item:
+            self.parents[item] = self.parents[self.parents[item]]
+            item = self.parents[item]
+
+        return item
+
+    def union(self, a, b):
+        root_a, root_b = self.find(a), self.find(b)
+
+        if root_a != root_b:
+            self.parents[max(root_a, root_b)] = min(root_a, root_b)
+
+    def groups(self) -> List[list]:
+        groups = defaultdict(list)
+
+        for item in self.parents:
+            groups[self.find(item)].append(item)
+
+        return sorted((sorted(group) for group in groups.values() if len(group) > 1), key=lambda group: group[0])
+
+
+def _image_bands(image_hash: str, max_distance: int) -> Iterable[Tuple[int, int]]:
+    """
+    Splits a hash into max_distance + 1 bands, so that by the pigeonhole principle any two
+    hashes within max_distance bits share at least one band
+    """
+    value = int(image_hash, 16)
+    band_count = min(max_distance + 1, IMAGE_HASH_BITS)
+
+    bounds = [round(i * IMAGE_HASH_BITS / band_count) for i in range(band_count + 1)]
+
+    for band, (start, end) in enumerate(zip(bounds, bounds[1:])):
+        yield band, (value >> start) & ((1 << (end - start)) - 1)
+
+
+def _candidate_pairs(
+    pages: List[Tuple[Tuple[int, int], PageFingerprint]], max_image_distance: int
+) -> Set[Tuple[int, int]]:
+    """
+    Finds pairs of pages that may be similar through locality-sensitive hashing, rather than comparing every pair
+    """
+    buckets = defaultdict(list)
+    rows = MINHASH_PERMUTATIONS // MINHASH_BANDS
+
+    for index, (_, fingerprint) in enumerate(pages):
+        for band, value in _image_bands(fingerprint.image_hash, max_image_distance):
+            buckets[("image", band, value)].append(index)
+
+        if fingerprint.text_hash is not None:
+            for band in range(MINHASH_BANDS):
+                buckets[("text", band, tuple(fingerprint.text_hash[band * rows : (band + 1) * rows]))].append(index)
+
+    pairs = set()
+
+    for indices in buckets.values():
+        for i, a in enumerate(indices):
+            for b in indices[i + 1 :]:
+                pairs.add((a, b))
+
+    return pairs
+
+
+def find_near_duplicates(
+    documents: List[Union["Document", DocumentFingerprint]],
+    *,
+    image_threshold: float = 0.9,
+    text_threshold: float = 0.6,
+    document_threshold: float = 0.8,
+    provider_name: Optional[str] = None,
+) -> NearDuplicates:
+    """
+    Groups near-duplicate pages and documents, e.g. re-saved or re-scanned copies.
+
+    Pages that both have text match if their shingles are at least `text_threshold` similar,
+    since many unrelated pages of text look alike at hashing resolution. Other pages match
+    if their image hashes are at least `image_threshold` similar. Two documents match if at least
+    `document_threshold` of the pages of each have a match in the other. Fingerprints can
+    be passed instead of documents, so they can be computed once and stored.
+    """
+    fingerprints = [
+        document if isinstance(document, DocumentFingerprint) else fingerprint_document(document, provider_name)
+        for document in documents
+    ]
+
+    pages = [
+        ((document_index, page.page_number), page)
+        for document_index, fingerprint in enumerate(fingerprints)
+        for page in fingerprint.pages
+        if not page.is_blank
+    ]
+
+    max_image_distance = int((1 - image_threshold) * IMAGE_HASH_BITS)
+
+    page_groups = _UnionFind()
+    matched_pages: Dict[Tuple[int, int], Set[int]] = defaultdict(set)  # (document, other document) -> page numbers
+
+    for a, b in _candidate_pairs(pages, max_image_distance):
+        (key_a, page_a), (key_b, page_b) = pages[a], pages[b]
+
+        text_score = text_similarity(page_a, page_b)
+
+        if text_score is not None:
+            if text_score < text_threshold:
+                continue
+        elif image_similarity(page_a, page_b) < image_threshold:
+            continue
+
+        page_groups.union(key_a, key_b)
+
+        if key_a[0] != key_b[0]:
+            matched_pages[(key_a[0], key_b[0])].add(key_a[1])
+            matched_pages[(key_b[0], key_a[0])].add(key_b[1])
+
+    # Blank pages are never matched, so they don't count against a document's coverage either
+    page_counts = [max(sum(1 for page in fingerprint.pages if not page.is_blank), 1) for fingerprint in fingerprints]
+
+    document_groups = _UnionFind()
+
+    for document_a, document_b in matched_pages:
+        if document_a > document_b:
+            continue
+
+        coverage_a = len(matched_pages[(document_a, document_b)]) / page_counts[document_a]
+        coverage_b = len(matched_pages[(document_b, document_a)]) / page_counts[document_b]
+
+        if min(coverage_a, coverage_b) >= document_threshold:
+            document_groups.union(document_a, document_b)
+
+    return NearDuplicates(document_groups=document_groups.groups(), page_groups=page_groups.groups())
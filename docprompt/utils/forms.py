@@ -0,0 +1,439 @@
+from io import BytesIO
+from typing import TYPE_CHECKING, Dict, List, Literal, Optional, Union
+
+from pydantic import BaseModel, Field
+from pypdf import PageObject, PdfReader, PdfWriter
+from pypdf.generic import (
+    ArrayObject,
+    BooleanObject,
+    DecodedStreamObject,
+    DictionaryObject,
+    FloatObject,
+    NameObject,
+    TextStringObject,
+)
+
+from docprompt.schema.layout import BoundingPoly, Geometry, NormBBox, TextBlock
+from docprompt.schema.page_geometry import PageGeometry
+
+if TYPE_CHECKING:
+    from docprompt.schema.document import Document
+
+FormFieldType = Literal["text", "checkbox", "radio", "choice", "signature", "button"]
+
+FormFieldValue = Union[str, bool, List[str]]
+
+# Field flags, from table 221 and onward of the PDF 1.7 spec (bit positions are 1-indexed there)
+READ_ONLY_FLAG = 1 << 0
+MULTILINE_FLAG = 1 << 12
+RADIO_FLAG = 1 << 15
+PUSH_BUTTON_FLAG = 1 << 16
+EDIT_FLAG = 1 << 18  # A combo box that also accepts values outside of its options
+
+APPEARANCE_FONT_NAME = "/Helv"
+DEFAULT_APPEARANCE_FONT_SIZE = 12
+
+
+class FormField(BaseModel):
+    """
+    Represents a single widget of an AcroForm field. Fields with several widgets, such
+    as radio groups or fields repeated across pages, appear once per widget.
+    """
+
+    name: str = Field(description="The fully qualified name of the field, e.g. 'applicant.address.city'")
+    type: FormFieldType
+    value: Optional[FormFieldValue] = Field(
+        default=None,
+        description="The value of the field. Checkboxes are booleans, and multi-select choices are lists",
+    )
+    page_number: int = Field(description="The 1-indexed page the widget is placed on")
+    bounding_box: NormBBox
+    options: List[str] = Field(
+        default_factory=list,
+        description="The allowed values of a choice field, or the export values of a checkbox or radio group",
+    )
+    read_only: bool = False
+    label: Optional[str] = Field(default=None, description="The field's tooltip, which often holds a readable label")
+
+    @property
+    def display_value(self) -> str:
+        if self.type == "checkbox":
+            return "[x]" if self.value else "[ ]"
+
+        if isinstance(self.value, list):
+            return ", ".join(self.value)
+
+        return self.value or ""
+
+    def to_text_block(self) -> TextBlock:
+        """
+        Returns the value of the field as a block of text placed where the widget is drawn
+        """
+        return TextBlock(
+            text=self.display_value,
+            type="block",
+            geometry=Geometry(
+                bounding_box=self.bounding_box, bounding_poly=BoundingPoly.from_norm_bbox(self.bounding_box)
+            ),
+            confidence=1.0,
+        )
+
+
+def _get_inherited(field: DictionaryObject, key: str):
+    """
+    Reads a field attribute, walking up the field hierarchy for inheritable ones like /FT and /V
+    """
+    while field is not None:
+        if key in field:
+            return field[key]
+
+        field = field.get("/Parent")
+        field = field.get_object() if field is not None else None
+
+    return None
+
+
+def _get_field(widget: DictionaryObject) -> DictionaryObject:
+    """
+    Returns the terminal field a widget belongs to, which may be the widget itself
+    """
+    if "/T" in widget or "/Parent" not in widget:
+        return widget
+
+    return widget["/Parent"].get_object()
+
+
+def _get_qualified_name(field: DictionaryObject) -> str:
+    parts = []
+
+    while field is not None:
+        if "/T" in field:
+            parts.append(str(field["/T"]))
+
+        field = field.get("/Parent")
+        field = field.get_object() if field is not None else None
+
+    return ".".join(reversed(parts))
+
+
+def _get_field_type(field: DictionaryObject) -> Optional[FormFieldType]:
+    field_type = _get_inherited(field, "/FT")
+    flags = int(_get_inherited(field, "/Ff") or 0)
+
+    if field_type == "/Tx":
+        return "text"
+    elif field_type == "/Ch":
+        return "choice"
+    elif field_type == "/Sig":
+        return "signature"
+    elif field_type == "/Btn":
+        if flags & PUSH_BUTTON_FLAG:
+            return "button"
+        elif flags & RADIO_FLAG:
+            return "radio"
+
+        return "checkbox"
+
+    return None
+
+
+def _get_on_states(widget: DictionaryObject) -> List[str]:
+    """
+    Returns the names of the "on" appearance states of a checkbox or radio button widget
+    """
+    appearances = widget["/AP"] if "/AP" in widget else None
+    normal = appearances["/N"] if appearances is not None and "/N" in appearances else None
+
+    if not isinstance(normal, DictionaryObject):
+        return []
+
+    return [str(state)[1:] for state in normal.keys() if state != "/Off"]
+
+
+def _get_widgets(field: DictionaryObject) -> List[DictionaryObject]:
+    if "/Kids" not in field:
+        return [field]
+
+    return [kid.get_object() for kid in field["/Kids"]]
+
+
+def _get_options(field: DictionaryObject, field_type: FormFieldType) -> List[str]:
+    if field_type == "choice":
+        options = _get_inherited(field, "/Opt") or []
+
+        # Each option is either a string, or an [export value, display text] pair
+        return [str(option[0]) if isinstance(option, ArrayObject) else str(option) for option in options]
+
+    if field_type in ("checkbox", "radio"):
+        states = []
+
+        for widget in _get_widgets(field):
+            states.extend(state for state in _get_on_states(widget) if state not in states)
+
+        return states
+
+    return []
+
+
+def _get_value(field: DictionaryObject, field_type: FormFieldType) -> Optional[FormFieldValue]:
+    value = _get_inherited(field, "/V")
+
+    if field_type == "checkbox":
+        return value is not None and value != "/Off"
+
+    if value is None:
+        return None
+
+    if isinstance(value, NameObject):
+        return None if value == "/Off" else str(value)[1:]
+
+    if isinstance(value, ArrayObject):
+        return [str(item) for item in value]
+
+    if field_type == "signature":
+        return None  # A signature value is a dictionary, not something we can show as text
+
+    return str(value)
+
+
+def _iter_page_widgets(pdf_page: PageObject):
+    annotations = pdf_page["/Annots"] if "/Annots" in pdf_page else []
+
+    for annotation in annotations:
+        annotation = annotation.get_object()
+
+        if annotation.get("/Subtype") == "/Widget" and "/Rect" in annotation:
+            yield annotation
+
+
+def get_page_form_fields(pdf_page: PageObject, page_number: int) -> List[FormField]:
+    """
+    Returns the form fields placed on a single page
+    """
+    geometry = PageGeometry.from_pdf_page(pdf_page)
+    fields = []
+
+    for widget in _iter_page_widgets(pdf_page):
+        field = _get_field(widget)
+        field_type = _get_field_type(field)
+
+        if field_type is None:
+            continue
+
+        label = _get_inherited(field, "/TU")
+
+        fields.append(
+            FormField(
+                name=_get_qualified_name(field),
+                type=field_type,
+                value=_get_value(field, field_type),
+                page_number=page_number,
+                bounding_box=geometry.bbox_from_user_space(*(float(v) for v in widget["/Rect"])),
+                options=_get_options(field, field_type),
+                read_only=bool(int(_get_inherited(field, "/Ff") or 0) & READ_ONLY_FLAG),
+                label=str(label) if label is not None else None,
+            )
+        )
+
+    return fields
+
+
+def get_form_fields(file_bytes: bytes, pages: Optional[List[int]] = None) -> List[FormField]:
+    """
+    Returns the AcroForm fields of a PDF, in page order
+    """
+    reader = PdfReader(BytesIO(file_bytes))
+
+    if "/AcroForm" not in reader.trailer["/Root"]:
+        return []
+
+    fields = []
+
+    for page_number, pdf_page in enumerate(reader.pages, start=1):
+        if pages is not None and page_number not in pages:
+            continue
+
+        fields.extend(get_page_form_fields(pdf_page, page_number))
+
+    return fields
+
+
+def _encode_text(text: str) -> str:
+    return "<" + text.encode("cp1252", errors="replace").hex() + ">"
+
+
+def _get_font_size(field: DictionaryObject, height: float) -> float:
+    """
+    Reads the font size from the field's default appearance string, where 0 means auto-size
+    """
+    default_appearance = str(_get_inherited(field, "/DA") or "")
+    tokens = default_appearance.split()
+
+    for index, token in enumerate(tokens):
+        if token == "Tf" and index > 0:
+            try:
+                size = float(tokens[index - 1])
+            except ValueError:
+                break
+
+            if size > 0:
+                return size
+
+            break
+
+    return min(DEFAULT_APPEARANCE_FONT_SIZE, max(height * 0.7, 1))
+
+
+def _build_text_appearance(field: DictionaryObject, widget: DictionaryObject, text: str) -> DecodedStreamObject:
+    """
+    Builds a simple appearance stream showing a field's value in Helvetica, so that
+    renderers that don't regenerate appearances (including Ghostscript) show the new value
+    """
+    x0, y0, x1, y1 = (float(v) for v in widget["/Rect"])
+    width, height = abs(x1 - x0), abs(y1 - y0)
+
+    font_size = _get_font_size(field, height)
+    multiline = bool(int(_get_inherited(field, "/Ff") or 0) & MULTILINE_FLAG)
+
+    lines = text.splitlines() if multiline else [" ".join(text.splitlines())]
+    leading = font_size * 1.15
+
+    if multiline:
+        y = height - 2 - font_size
+    else:
+        y = (height - font_size) / 2 + font_size * 0.22
+
+    operations = ["/Tx BMC", "q", f"1 1 {width - 2:.2f} {height - 2:.2f} re W n", "BT"]
+    operations.append(f"{APPEARANCE_FONT_NAME} {font_size:.2f} Tf 0 g")
+    operations.append(f"2 {y:.2f} Td {leading:.2f} TL")
+
+    for index, line in enumerate(lines):
+        if index > 0:
+            operations.append("T*")
+
+        operations.append(f"{_encode_text(line)} Tj")
+
+    operations += ["ET", "Q", "EMC"]
+
+    font = DictionaryObject(
+        {
+            NameObject("/Type"): NameObject("/Font"),
+            NameObject("/Subtype"): NameObject("/Type1"),
+            NameObject("/BaseFont"): NameObject("/Helvetica"),
+            NameObject("/Encoding"): NameObject("/WinAnsiEncoding"),
+        }
+    )
+
+    stream = DecodedStreamObject()
+    stream.set_data("\n".join(operations).encode("latin-1"))
+    stream.update(
+        {
+            NameObject("/Type"): NameObject("/XObject"),
+            NameObject("/Subtype"): NameObject("/Form"),
+            NameObject("/BBox"): ArrayObject([FloatObject(0), FloatObject(0), FloatObject(width), FloatObject(height)]),
+            NameObject("/Resources"): DictionaryObject(
+                {NameObject("/Font"): DictionaryObject({NameObject(APPEARANCE_FONT_NAME): font})}
+            ),
+        }
+    )
+
+    return stream
+
+
+def _fill_field(writer: PdfWriter, field: DictionaryObject, field_type: FormFieldType, value: FormFieldValue):
+    name = _get_qualified_name(field)
+
+    if field_type in ("signature", "button"):
+        raise ValueError(f"Field '{name}' is a {field_type} and can't be filled")
+
+    if field_type == "checkbox":
+        if not isinstance(value, bool):
+            raise ValueError(f"Field '{name}' is a checkbox and must be filled with a boolean")
+
+        for widget in _get_widgets(field):
+            on_states = _get_on_states(widget)
+            state = NameObject("/" + on_states[0]) if value and on_states else NameObject("/Off")
+
+            widget[NameObject("/AS")] = state
+            field[NameObject("/V")] = state
+
+        return
+
+    if field_type == "radio":
+        options = _get_options(field, field_type)
+
+        if value not in options:
+            raise ValueError(f"Field '{name}' must be one of {options}, got '{value}'")
+
+        field[NameObject("/V")] = NameObject("/" + value)
+
+        for widget in _get_widgets(field):
+            widget[NameObject("/AS")] = NameObject("/" + value if value in _get_on_states(widget) else "/Off")
+
+        return
+
+    if isinstance(value, bool):
+        raise ValueError(f"Field '{name}' is a {field_type} field and can't be filled with a boolean")
+
+    if isinstance(value, list) and field_type != "choice":
+        raise ValueError(f"Field '{name}' is a {field_type} field and can't be filled with a list")
+
+    if field_type == "choice" and not int(_get_inherited(field, "/Ff") or 0) & EDIT_FLAG:
+        options = _get_options(field, field_type)
+        invalid = [item for item in (value if isinstance(value, list) else [value]) if item not in options]
+
+        if invalid:
+            raise ValueError(f"Field '{name}' must be one of {options}, got {invalid}")
+
+    if isinstance(value, list):
+
+        field[NameObject("/V")] = ArrayObject([TextStringObject(item) for item in value])
+        text = ", ".join(value)
+    else:
+        field[NameObject("/V")] = TextStringObject(value)
+        text = value
+
+    for widget in _get_widgets(field):
+        appearance = writer.add_object(_build_text_appearance(field, widget, text))
+        widget[NameObject("/AP")] = DictionaryObject({NameObject("/N"): appearance})
+
+
+def fill_form(document: "Document", values: Dict[str, FormFieldValue]) -> "Document":
+    """
+    Returns a new document with the given form fields filled, keyed by fully qualified name.
+
+    Text and choice fields take strings (or a list of strings for multi-select choices),
+    checkboxes take booleans and radio groups take one of their export values. Appearance
+    streams are regenerated for the filled fields, so the values show up when rasterized.
+    """
+    reader = PdfReader(BytesIO(document.file_bytes))
+
+    if "/AcroForm" not in reader.trailer["/Root"]:
+        raise ValueError(f"Document {document} does not have any form fields")
+
+    writer = PdfWriter(clone_from=reader)
+
+    fields: Dict[str, DictionaryObject] = {}
+
+    for pdf_page in writer.pages:
+        for widget in _iter_page_widgets(pdf_page):
+            field = _get_field(widget)
+            fields.setdefault(_get_qualified_name(field), field)
+
+    unknown = [name for name in values if name not in fields]
+
+    if unknown:
+        raise ValueError(f"Document {document} does not have form fields named {unknown}")
+
+    for name, value in values.items():
+        field = fields[name]
+        _fill_field(writer, field, _get_field_type(field), value)
+
+    # Lets viewers that can regenerate appearances replace our simple ones
+    acro_form = writer.root_object["/AcroForm"].get_object()
+    acro_form[NameObject("/NeedAppearances")] = BooleanObject(True)
+
+    output_stream = BytesIO()
+    writer.write(output_stream)
+
+    return document._with_file_bytes(output_stream.getvalue())
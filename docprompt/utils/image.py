@@ -0,0 +1,221 @@
+import zlib
+from io import BytesIO
+from os import PathLike
+from pathlib import Path
+from typing import List, Optional, Tuple, Union
+
+import magic
+from PIL import Image, ImageOps, ImageSequence
+from pypdf import PdfWriter
+from pypdf.generic import DecodedStreamObject, DictionaryObject, NameObject, NumberObject
+
+from docprompt._exec.ghostscript import split_jpeg_images
+
+SUPPORTED_IMAGE_MIME_TYPES = ["image/png", "image/jpeg", "image/tiff"]
+
+DEFAULT_IMAGE_DPI = 200  # Used when an image carries no (or nonsensical) resolution metadata
+
+EXIF_ORIENTATION = 0x0112
+
+# Formats whose extra frames are pages. The extra frames of an MPO (which is how Pillow opens
+# many phone photos) are previews and depth maps of the same photo, so only its first is used.
+MULTI_PAGE_FORMATS = ("TIFF", "GIF")
+
+# The modes `_prepare_frame` produces, and how their pixels are described in a PDF
+PDF_COLOR_SPACES = {"1": "/DeviceGray", "L": "/DeviceGray", "RGB": "/DeviceRGB", "CMYK": "/DeviceCMYK"}
+
+ImageSource = Union[Image.Image, bytes, PathLike, str]
+DPIInput = Union[int, float, Tuple[float, float]]
+
+
+def get_image_mime_type(fd: Union[Path, PathLike, bytes]) -> Optional[str]:
+    """
+    Returns the mime type of an image, or None if it is not a supported image
+    """
+    if not isinstance(fd, bytes):
+        with open(fd, "rb") as f:
+            fd: bytes = f.read(1024)
+
+    mime = magic.from_buffer(fd, mime=True)
+
+    return mime if mime in SUPPORTED_IMAGE_MIME_TYPES else None
+
+
+def is_image(fd: Union[Path, PathLike, bytes]) -> bool:
+    """
+    Determines if a file is a supported image (PNG, JPEG or TIFF)
+    """
+    return get_image_mime_type(fd) is not None
+
+
+def get_image_dpi(image: Image.Image, default: DPIInput = DEFAULT_IMAGE_DPI) -> Tuple[float, float]:
+    """
+    Returns the (x, y) resolution of an image, falling back to `default` if the
+    image does not carry usable resolution metadata
+    """
+    dpi = image.info.get("dpi")
+
+    try:
+        x_dpi, y_dpi = float(dpi[0]), float(dpi[1])
+    except (TypeError, IndexError, ValueError):
+        x_dpi = y_dpi = 0.0
+
+    if x_dpi < 1 or y_dpi < 1:
+        return _normalize_dpi(default)
+
+    return x_dpi, y_dpi
+
+
+def _normalize_dpi(dpi: DPIInput) -> Tuple[float, float]:
+    if isinstance(dpi, (int, float)):
+        return float(dpi), float(dpi)
+
+    return float(dpi[0]), float(dpi[1])
+
+
+def _prepare_frame(frame: Image.Image) -> Image.Image:
+    """
+    Converts a frame into a mode that can be embedded in a PDF without resampling
+    """
+    if frame.mode in ("1", "L", "RGB", "CMYK"):
+        return frame.copy()
+
+    if frame.mode in ("RGBA", "LA") or (frame.mode == "P" and "transparency" in frame.info):
+        rgba = frame.convert("RGBA")
+        background = Image.new("RGB", rgba.size, (255, 255, 255))
+        background.paste(rgba, mask=rgba.getchannel("A"))
+        return background
+
+    if frame.mode.startswith("I;16"):
+        # 16-bit grayscale (common in scanner TIFFs) needs to be scaled down, not clipped
+        return frame.convert("I").point(lambda v: v * (1 / 256)).convert("L")
+
+    if frame.mode in ("I", "F"):
+        return frame.convert("L")
+
+    return frame.convert("RGB")
+
+
+def open_image_frames(source: ImageSource) -> List[Tuple[Image.Image, Tuple[float, float]]]:
+    """
+    Opens an image source and returns each of its frames along with the frame's resolution.
+
+    Multi-page TIFFs and GIFs yield one frame per page, and every other format yields its
+    first frame. EXIF orientation (common on phone photos) is applied so that the frame is upright.
+    """
+    if isinstance(source, Image.Image):
+        image = source
+    elif isinstance(source, bytes):
+        image = Image.open(BytesIO(source))
+    else:
+        image = Image.open(Path(source))
+
+    frames = []
+    pages = ImageSequence.Iterator(image) if image.format in MULTI_PAGE_FORMATS else [image]
+
+    for frame in pages:
+        dpi = get_image_dpi(frame)
+        frame = ImageOps.exif_transpose(frame)
+        frames.append((_prepare_frame(frame), dpi))
+
+    return frames
+
+
+def _read_source_bytes(source: ImageSource) -> Optional[bytes]:
+    if isinstance(source, Image.Image):
+        return None
+
+    if isinstance(source, bytes):
+        return source
+
+    return Path(source).read_bytes()
+
+
+def _is_embeddable_jpeg(image: Image.Image) -> bool:
+    """
+    Whether a JPEG's original stream can be embedded in a PDF as-is. CMYK JPEGs are often
+    stored inverted, and an EXIF orientation would need the pixels rotated, so those are
+    decoded and embedded losslessly instead.
+    """
+    return (
+        image.format in ("JPEG", "MPO")
+        and image.mode in ("L", "RGB")
+        and image.getexif().get(EXIF_ORIENTATION, 1) == 1
+    )
+
+
+def _build_image_xobject(frame: Image.Image, jpeg_data: Optional[bytes]) -> DecodedStreamObject:
+    """
+    Builds an image XObject, passing a JPEG's stream through untouched and compressing any other
+    pixels with Flate, so that no generation is lost to re-encoding
+    """
+    if jpeg_data is not None:
+        data, stream_filter = jpeg_data, "/DCTDecode"
+    else:
+        data, stream_filter = zlib.compress(frame.tobytes()), "/FlateDecode"
+
+    xobject = DecodedStreamObject()
+    xobject.set_data(data)
+    xobject.update(
+        {
+            NameObject("/Type"): NameObject("/XObject"),
+            NameObject("/Subtype"): NameObject("/Image"),
+            NameObject("/Width"): NumberObject(frame.width),
+            NameObject("/Height"): NumberObject(frame.height),
+            NameObject("/ColorSpace"): NameObject(PDF_COLOR_SPACES[frame.mode]),
+            NameObject("/BitsPerComponent"): NumberObject(1 if frame.mode == "1" else 8),
+            NameObject("/Filter"): NameObject(stream_filter),
+        }
+    )
+
+    return xobject
+
+
+def images_to_pdf_bytes(images: List[ImageSource], *, dpi: Optional[DPIInput] = None) -> bytes:
+    """
+    Wraps one or more images into a PDF, with one page per image frame.
+
+    JPEGs are embedded with their original stream, and everything else is compressed losslessly.
+    The page size is derived from each frame's own resolution (or `dpi`, if given) so that
+    rasterizing the page back at that resolution reproduces the original pixel dimensions.
+    """
+    frames: List[Tuple[Image.Image, Tuple[float, float], Optional[bytes]]] = []
+
+    for source in images:
+        data = _read_source_bytes(source)
+        image = Image.open(BytesIO(data)) if data is not None else source
+
+        if data is not None and _is_embeddable_jpeg(image):
+            # An MPO is a series of JPEGs, the first of which is the photo itself
+            jpeg_data = split_jpeg_images(data)[0] if image.format == "MPO" else data
+            frames.append((image, get_image_dpi(image), jpeg_data))
+        else:
+            frames.extend((frame, frame_dpi, None) for frame, frame_dpi in open_image_frames(image))
+
+    if not frames:
+        raise ValueError("Must provide at least one image")
+
+    writer = PdfWriter()
+
+    for frame, frame_dpi, jpeg_data in frames:
+        # Non-square resolutions, such as those used by fax TIFFs, are kept by scaling each axis separately
+        x_dpi, y_dpi = _normalize_dpi(dpi) if dpi is not None else frame_dpi
+        width, height = frame.width * 72 / x_dpi, frame.height * 72 / y_dpi
+
+        page = writer.add_blank_page(width=width, height=height)
+        page[NameObject("/Resources")] = DictionaryObject(
+            {
+                NameObject("/XObject"): DictionaryObject(
+                    {NameObject("/Im0"): writer.add_object(_build_image_xobject(frame, jpeg_data))}
+                )
+            }
+        )
+
+        contents = DecodedStreamObject()
+        contents.set_data(f"q {width:.4f} 0 0 {height:.4f} 0 0 cm /Im0 Do Q".encode())
+        page[NameObject("/Contents")] = writer.add_object(contents)
+
+    output_stream = BytesIO()
+    writer.write(output_stream)
+
+    return output_stream.getvalue()
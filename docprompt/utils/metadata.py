@@ -0,0 +1,248 @@
+from datetime import datetime
+from io import BytesIO
+from typing import Dict, Iterator, List, Optional
+
+from pydantic import BaseModel, Field
+from pypdf import PdfReader
+
+# Keys of the Info dictionary that are exposed as typed fields, rather than in `DocumentInfo.custom`
+STANDARD_INFO_KEYS = (
+    "/Title",
+    "/Author",
+    "/Subject",
+    "/Keywords",
+    "/Creator",
+    "/Producer",
+    "/CreationDate",
+    "/ModDate",
+    "/Trapped",
+)
+
+
+class DocumentInfo(BaseModel):
+    """
+    The contents of a PDF's Info dictionary
+    """
+
+    title: Optional[str] = None
+    author: Optional[str] = None
+    subject: Optional[str] = None
+    keywords: Optional[str] = None
+    creator: Optional[str] = Field(default=None, description="The application that created the original document")
+    producer: Optional[str] = Field(default=None, description="The application that converted it to PDF")
+    created_at: Optional[datetime] = None
+    modified_at: Optional[datetime] = None
+    custom: Dict[str, str] = Field(default_factory=dict, description="Any non-standard Info dictionary entries")
+
+
+class XmpMetadata(BaseModel):
+    """
+    The commonly used fields of a PDF's XMP metadata stream, along with the raw XML
+    """
+
+    title: Optional[str] = None
+    creators: List[str] = Field(default_factory=list)
+    description: Optional[str] = None
+    subjects: List[str] = Field(default_factory=list)
+    creator_tool: Optional[str] = None
+    producer: Optional[str] = None
+    keywords: Optional[str] = None
+    created_at: Optional[datetime] = None
+    modified_at: Optional[datetime] = None
+    raw: str = Field(repr=False)
+
+
+class OutlineItem(BaseModel):
+    """
+    A single bookmark in a PDF's outline
+    """
+
+    title: str
+    level: int = Field(description="The depth of the bookmark in the outline, starting at 1")
+    page_number: Optional[int] = Field(default=None, description="The 1-indexed target page, if it could be resolved")
+    children: List["OutlineItem"] = Field(default_factory=list)
+
+
+class DocumentMetadata(BaseModel):
+    """
+    The document-level metadata of a PDF
+    """
+
+    info: DocumentInfo = Field(default_factory=DocumentInfo)
+    xmp: Optional[XmpMetadata] = None
+    outline: List[OutlineItem] = Field(default_factory=list, repr=False)
+    page_labels: List[str] = Field(
+        default_factory=list, description="The logical label of each page, e.g. 'iv' or 'A-3'", repr=False
+    )
+
+    def iter_outline(self) -> Iterator[OutlineItem]:
+        """
+        Iterates over every bookmark in the outline, depth-first
+        """
+        stack = list(reversed(self.outline))
+
+        while stack:
+            item = stack.pop()
+            yield item
+            stack.extend(reversed(item.children))
+
+    def get_table_of_contents(self, include_page_labels: bool = True) -> str:
+        """
+        Renders the outline as an indented table of contents, e.g. for inclusion in a prompt.
+        Pages are shown by their label if `include_page_labels` is set, and by number otherwise.
+        """
+        lines = []
+
+        for item in self.iter_outline():
+            line = "  " * (item.level - 1) + item.title
+
+            if item.page_number is not None:
+                page = item.page_number
+
+                if include_page_labels and len(self.page_labels) >= item.page_number:
+                    page = self.page_labels[item.page_number - 1]
+
+                line += f" ... {page}"
+
+            lines.append(line)
+
+        return "\n".join(lines)
+
+
+def _optional_text(value) -> Optional[str]:
+    if value is None:
+        return None
+
+    return str(value.get_object() if hasattr(value, "get_object") else value).strip() or None
+
+
+def _safe_get(obj, attribute: str):
+    """
+    Reads a parsed metadata property, which pypdf raises on when the underlying value is malformed
+    """
+    try:
+        return getattr(obj, attribute)
+    except Exception:
+        return None
+
+
+def get_document_info(reader: PdfReader) -> DocumentInfo:
+    info = reader.metadata
+
+    if info is None:
+        return DocumentInfo()
+
+    return DocumentInfo(
+        title=_optional_text(info.get("/Title")),
+        author=_optional_text(info.get("/Author")),
+        subject=_optional_text(info.get("/Subject")),
+        keywords=_optional_text(info.get("/Keywords")),
+        creator=_optional_text(info.get("/Creator")),
+        producer=_optional_text(info.get("/Producer")),
+        created_at=_safe_get(info, "creation_date"),
+        modified_at=_safe_get(info, "modification_date"),
+        custom={
+            key[1:]: str(value.get_object())
+            for key, value in info.items()
+            if key not in STANDARD_INFO_KEYS and value is not None
+        },
+    )
+
+
+def _language_alternative(value) -> Optional[str]:
+    """
+    XMP stores text like titles per language, and we want the default one
+    """
+    if not value:
+        return None
+
+    return value.get("x-default") or next(iter(value.values()), None)
+
+
+def get_xmp_metadata(reader: PdfReader) -> Optional[XmpMetadata]:
+    root = reader.trailer["/Root"]
+
+    if "/Metadata" not in root:
+        return None
+
+    try:
+        raw = root["/Metadata"].get_object().get_data().decode("utf-8", errors="replace")
+        xmp = reader.xmp_metadata
+    except Exception:
+        return None  # Malformed XMP is common and shouldn't prevent reading the rest of the metadata
+
+    if xmp is None:
+        return None
+
+    return XmpMetadata(
+        title=_language_alternative(_safe_get(xmp, "dc_title")),
+        creators=list(_safe_get(xmp, "dc_creator") or []),
+        description=_language_alternative(_safe_get(xmp, "dc_description")),
+        subjects=list(_safe_get(xmp, "dc_subject") or []),
+        creator_tool=_optional_text(_safe_get(xmp, "xmp_creator_tool")),
+        producer=_optional_text(_safe_get(xmp, "pdf_producer")),
+        keywords=_optional_text(_safe_get(xmp, "pdf_keywords")),
+        created_at=_safe_get(xmp, "xmp_create_date"),
+        modified_at=_safe_get(xmp, "xmp_modify_date"),
+        raw=raw,
+    )
+
+
+def _build_outline(reader: PdfReader, items: list, level: int) -> List[OutlineItem]:
+    outline: List[OutlineItem] = []
+
+    for item in items:
+        # pypdf represents children as a list directly following their parent
+        if isinstance(item, list):
+            children = _build_outline(reader, item, level + 1)
+
+            if outline:
+                outline[-1].children.extend(children)
+            else:
+                outline.extend(children)
+
+            continue
+
+        try:
+            page_index = reader.get_destination_page_number(item)
+        except Exception:
+            page_index = None
+
+        outline.append(
+            OutlineItem(
+                title=str(item.title or "").strip(),
+                level=level,
+                page_number=page_index + 1 if page_index is not None and page_index >= 0 else None,
+            )
+        )
+
+    return outline
+
+
+def get_outline(reader: PdfReader) -> List[OutlineItem]:
+    try:
+        return _build_outline(reader, reader.outline, 1)
+    except Exception:
+        return []
+
+
+def get_page_labels(reader: PdfReader) -> List[str]:
+    try:
+        return list(reader.page_labels)
+    except Exception:
+        # Fall back to physical page numbers, which is what viewers show without labels
+        return [str(page_number) for page_number in range(1, len(reader.pages) + 1)]
+
+
+def get_document_metadata(file_bytes: bytes) -> DocumentMetadata:
+    """
+    Reads the Info dictionary, XMP metadata, outline and page labels of a PDF
+    """
+    reader = PdfReader(BytesIO(file_bytes))
+
+    return DocumentMetadata(
+        info=get_document_info(reader),
+        xmp=get_xmp_metadata(reader),
+        outline=get_outline(reader),
+        page_labels=get_page_labels(reader),
+    )
@@ -0,0 +1,298 @@
+import logging
+import re
+import tempfile
+import warnings
+from contextlib import contextmanager
+from io import BytesIO
+from typing import TYPE_CHECKING, Callable, Dict, List, Literal, Optional, Tuple, Union
+
+import magic
+import pdfplumber
+from pydantic import BaseModel, Field
+from pypdf import PdfReader, PdfWriter
+
+from docprompt._exec.ghostscript import DEFAULT_TIMEOUT, ProcessLimits, rasterize_pdf_to_bytes, rewrite_pdf_to_bytes
+from docprompt.utils.image import images_to_pdf_bytes
+
+if TYPE_CHECKING:
+    from docprompt.schema.document import Document
+
+RepairStrategy = Literal["pypdf_rewrite", "ghostscript_pdfwrite", "rasterize_rebuild"]
+
+DEFAULT_REPAIR_STRATEGIES: List[RepairStrategy] = ["pypdf_rewrite", "ghostscript_pdfwrite", "rasterize_rebuild"]
+
+DEFAULT_REBUILD_DPI = 200
+
+# Damaged files are the ones most likely to hang Ghostscript
+REPAIR_PROCESS_LIMITS = ProcessLimits(timeout=DEFAULT_TIMEOUT)
+
+# pypdf warns about plenty of harmless quirks, only these mean the file's structure is broken
+STRUCTURAL_WARNING_PATTERN = re.compile(
+    r"xref|startxref|trailer|EOF marker|wrong pointing object|not defined|invalid parent|invalid stream",
+    re.IGNORECASE,
+)
+
+
+class RepairAttempt(BaseModel):
+    strategy: RepairStrategy
+    succeeded: bool
+    error: Optional[str] = None
+    page_count: Optional[int] = None
+
+
+class RepairReport(BaseModel):
+    """
+    Describes what was wrong with a PDF and how it was repaired
+    """
+
+    problems: List[str] = Field(default_factory=list, description="Problems found in the original file")
+    warnings: List[str] = Field(
+        default_factory=list, description="Harmless quirks found in the original file, which don't need a repair"
+    )
+    original_page_count: Optional[int] = Field(
+        default=None, description="The page count of the original file, if it could be read"
+    )
+    attempts: List[RepairAttempt] = Field(default_factory=list)
+    strategy: Optional[RepairStrategy] = Field(
+        default=None, description="The strategy that produced the repaired file, if any was needed"
+    )
+
+    @property
+    def needed_repair(self) -> bool:
+        return len(self.problems) > 0
+
+    @property
+    def succeeded(self) -> bool:
+        return not self.needed_repair or self.strategy is not None
+
+    @property
+    def lossy(self) -> bool:
+        """
+        Whether the repair threw away the text layer and vector content of the document
+        """
+        return self.strategy == "rasterize_rebuild"
+
+
+class DocumentRepairError(ValueError):
+    def __init__(self, message: str, report: RepairReport) -> None:
+        self.report = report
+        super().__init__(message)
+
+
+@contextmanager
+def _capture_pypdf_messages():
+    """
+    Collects the warnings pypdf emits (through both `logging` and `warnings`) while reading
+    """
+    messages: List[str] = []
+
+    class ListHandler(logging.Handler):
+        def emit(self, record):
+            messages.append(record.getMessage())
+
+    handler = ListHandler(level=logging.WARNING)
+    logger = logging.getLogger("pypdf")
+    logger.addHandler(handler)
+
+    try:
+        with warnings.catch_warnings(record=True) as caught:
+            warnings.simplefilter("always")
+            yield messages
+
+        messages.extend(str(warning.message) for warning in caught)
+    finally:
+        logger.removeHandler(handler)
+
+
+def _read_page_count(file_bytes: bytes) -> int:
+    """
+    Reads a PDF the way the rest of docprompt does, raising if any part of that fails
+    """
+    if magic.from_buffer(file_bytes, mime=True) != "application/pdf":
+        raise ValueError("File is not recognized as a PDF")
+
+    # Accessing each page's dimensions forces both libraries to parse the page tree
+    with pdfplumber.open(BytesIO(file_bytes)) as pdf:
+        page_count = len(pdf.pages)
+
+        for page in pdf.pages:
+            page.width, page.height
+
+    reader = PdfReader(BytesIO(file_bytes))
+
+    for page in reader.pages:
+        page.mediabox
+
+    if page_count == 0:
+        raise ValueError("Document has no pages")
+
+    return page_count
+
+
+def diagnose_pdf_bytes(file_bytes: bytes) -> Tuple[List[str], List[str], Optional[int]]:
+    """
+    Returns the structural problems found in a PDF, the harmless warnings pypdf raised
+    while reading it and its page count, if it could be read
+    """
+    problems = []
+    pypdf_warnings = []
+    page_count = None
+
+    header_index = file_bytes.find(b"%PDF-", 0, 1024)
+
+    if header_index == -1:
+        problems.append("Missing %PDF- header")
+    elif header_index > 0:
+        problems.append(f"Found {header_index} bytes of junk before the %PDF- header")
+
+    if b"%%EOF" not in file_bytes[-2048:]:
+        problems.append("Missing %%EOF marker, the file may be truncated")
+
+    with _capture_pypdf_messages() as messages:
+        try:
+            reader = PdfReader(BytesIO(file_bytes), strict=False)
+
+            for page in reader.pages:
+                page.get_contents()
+        except Exception as e:
+            problems.append(f"pypdf failed to read the document: {e}")
+
+    for message in dict.fromkeys(messages):
+        if STRUCTURAL_WARNING_PATTERN.search(message):
+            problems.append(f"pypdf: {message}")
+        else:
+            pypdf_warnings.append(f"pypdf: {message}")
+
+    try:
+        page_count = _read_page_count(file_bytes)
+    except Exception as e:
+        problems.append(f"Failed to read the page count: {e}")
+
+    return problems, pypdf_warnings, page_count
+
+
+def _pypdf_rewrite(file_bytes: bytes) -> bytes:
+    reader = PdfReader(BytesIO(file_bytes), strict=False)
+
+    # Cloning keeps the catalog, so forms, the outline, page labels and metadata survive
+    writer = PdfWriter(clone_from=reader)
+
+    output_stream = BytesIO()
+    writer.write(output_stream)
+
+    return output_stream.getvalue()
+
+
+def _ghostscript_pdfwrite(file_bytes: bytes) -> bytes:
+    with tempfile.NamedTemporaryFile(suffix=".pdf") as f:
+        f.write(file_bytes)
+        f.flush()
+
+        return rewrite_pdf_to_bytes(f.name, limits=REPAIR_PROCESS_LIMITS)
+
+
+def _rasterize_rebuild(file_bytes: bytes, dpi: int = DEFAULT_REBUILD_DPI) -> bytes:
+    with tempfile.NamedTemporaryFile(suffix=".pdf") as f:
+        f.write(file_bytes)
+        f.flush()
+
+        rasters = rasterize_pdf_to_bytes(f.name, dpi=dpi, device="png16m", limits=REPAIR_PROCESS_LIMITS)
+
+    if not rasters:
+        raise ValueError("Ghostscript did not render any pages")
+
+    return images_to_pdf_bytes([rasters[page_number] for page_number in sorted(rasters)], dpi=dpi)
+
+
+REPAIR_FUNCTIONS: Dict[RepairStrategy, Callable[[bytes], bytes]] = {
+    "pypdf_rewrite": _pypdf_rewrite,
+    "ghostscript_pdfwrite": _ghostscript_pdfwrite,
+    "rasterize_rebuild": _rasterize_rebuild,
+}
+
+
+def repair_pdf_bytes(
+    file_bytes: bytes, strategies: Optional[List[RepairStrategy]] = None, *, force: bool = False
+) -> Tuple[bytes, RepairReport]:
+    """
+    Tries each repair strategy in turn until one produces a readable PDF, and returns
+    the repaired bytes along with a report. Files without problems are returned as-is,
+    unless `force` is set.
+
+    Raises a `DocumentRepairError` carrying the report if every strategy fails.
+    """
+    problems, pypdf_warnings, original_page_count = diagnose_pdf_bytes(file_bytes)
+    report = RepairReport(problems=problems, warnings=pypdf_warnings, original_page_count=original_page_count)
+
+    if not problems and not force:
+        return file_bytes, report
+
+    header_index = file_bytes.find(b"%PDF-", 0, 1024)
+
+    if header_index > 0:
+        file_bytes = file_bytes[header_index:]  # Every strategy does better without the junk
+
+    for strategy in strategies or DEFAULT_REPAIR_STRATEGIES:
+        try:
+            repaired = REPAIR_FUNCTIONS[strategy](file_bytes)
+            page_count = _read_page_count(repaired)
+
+            if original_page_count is not None and page_count < original_page_count:
+                raise ValueError(f"Repaired document lost pages ({page_count} of {original_page_count} remain)")
+        except Exception as e:
+            report.attempts.append(RepairAttempt(strategy=strategy, succeeded=False, error=str(e)))
+            continue
+
+        report.attempts.append(RepairAttempt(strategy=strategy, succeeded=True, page_count=page_count))
+        report.strategy = strategy
+
+        return repaired, report
+
+    raise DocumentRepairError("Every repair strategy failed", report)
+
+
+def repair_document(
+    document: Union["Document", bytes],
+    strategies: Optional[List[RepairStrategy]] = None,
+    *,
+    name: Optional[str] = None,
+    force: bool = False,
+) -> Tuple["Document", RepairReport]:
+    """
+    Repairs a damaged PDF with a staged pipeline: a pypdf rewrite, then a Ghostscript
+    pdfwrite re-distill, then rasterizing every page and rebuilding the PDF from images.
+
+    Returns the repaired Document and a report of what was wrong and which strategy
+    worked. Text sidecars are carried along when the page count is unchanged.
+    """
+    from docprompt.schema.document import Document
+
+    if isinstance(document, Document):
+        file_bytes, report = repair_pdf_bytes(document.file_bytes, strategies, force=force)
+
+        if report.strategy is None:
+            return document, report
+
+        repaired = Document(
+            name=name or document.name,
+            file_path=document.file_path,
+            file_bytes=file_bytes,
+            source_mime_type=document.source_mime_type,
+        )
+
+        if report.original_page_count is not None and repaired.page_count == report.original_page_count:
+            repaired.text_sidecars = {
+                provider_name: {number: page_text.model_copy(deep=True) for number, page_text in sidecars.items()}
+                for provider_name, sidecars in document.text_sidecars.items()
+            }
+
+        repaired._repair_report = report
+
+        return repaired, report
+
+    file_bytes, report = repair_pdf_bytes(document, strategies, force=force)
+
+    repaired = Document.from_bytes(file_bytes, name=name)
+    repaired._repair_report = report
+
+    return repaired, report
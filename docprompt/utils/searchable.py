@@ -0,0 +1,144 @@
+from io import BytesIO
+from math import atan2, cos, hypot, sin
+from typing import TYPE_CHECKING, List, Optional
+
+from pypdf import PageObject, PdfReader, PdfWriter
+from pypdf.generic import ArrayObject, DecodedStreamObject, DictionaryObject, NameObject, NumberObject
+
+from docprompt.schema.layout import TextBlock
+from docprompt.schema.page_geometry import PageGeometry
+from docprompt.utils.util import format_number
+
+if TYPE_CHECKING:
+    from docprompt.schema.document import Document
+
+TEXT_LAYER_FONT_NAME = "/FDocpromptText"
+
+# Every glyph of the text layer font is given the same width, so that the width of a word
+# is known exactly and can be stretched to cover the word's bounding box.
+GLYPH_WIDTH = 500
+
+# Helvetica's descent, as a fraction of the font size. The baseline sits this far above the
+# bottom of a word's box so that the (invisible) selection area lines up with the glyphs.
+BASELINE_OFFSET = 0.2
+
+
+def _text_layer_font() -> DictionaryObject:
+    return DictionaryObject(
+        {
+            NameObject("/Type"): NameObject("/Font"),
+            NameObject("/Subtype"): NameObject("/Type1"),
+            NameObject("/BaseFont"): NameObject("/Helvetica"),
+            NameObject("/Encoding"): NameObject("/WinAnsiEncoding"),
+            NameObject("/FirstChar"): NumberObject(0),
+            NameObject("/LastChar"): NumberObject(255),
+            NameObject("/Widths"): ArrayObject([NumberObject(GLYPH_WIDTH)] * 256),
+        }
+    )
+
+
+def text_layer_operations(pdf_page: PageObject, words: List[TextBlock]) -> bytes:
+    """
+    Returns content stream operations that draw each word as invisible text, stretched to
+    cover the word's geometry and rotated to follow the skew of its bounding poly
+    """
+    geometry = PageGeometry.from_pdf_page(pdf_page)
+    operations = ["q", "BT", "3 Tr"]  # Text rendering mode 3 is invisible
+
+    for word in words:
+        text = word.text.strip()
+
+        if not text:
+            continue
+
+        if word.geometry.bounding_poly is not None and len(word.geometry.bounding_poly.normalized_vertices) == 4:
+            top_left, _, bottom_right, bottom_left = word.geometry.bounding_poly.normalized_vertices
+            top_left = (top_left.x, top_left.y)
+            bottom_right = (bottom_right.x, bottom_right.y)
+            bottom_left = (bottom_left.x, bottom_left.y)
+        else:
+            bbox = word.bounding_box
+            top_left, bottom_right, bottom_left = (bbox.x0, bbox.top), (bbox.x1, bbox.bottom), (bbox.x0, bbox.bottom)
+
+        origin_x, origin_y = geometry.normalized_to_user_space(*bottom_left)
+        end_x, end_y = geometry.normalized_to_user_space(*bottom_right)
+        top_x, top_y = geometry.normalized_to_user_space(*top_left)
+
+        width = hypot(end_x - origin_x, end_y - origin_y)
+        height = hypot(top_x - origin_x, top_y - origin_y)
+
+        if width == 0 or height == 0:
+            continue
+
+        angle = atan2(end_y - origin_y, end_x - origin_x)
+        font_size = height
+
+        encoded = text.encode("cp1252", errors="replace")
+        natural_width = len(encoded) * GLYPH_WIDTH / 1000 * font_size
+        horizontal_scaling = 100 * width / natural_width
+
+        # Trailing space so that viewers separate words when copying text. It's drawn past the
+        # end of the box, so that the word itself covers the box exactly.
+        encoded += b" "
+
+        # Raise the baseline perpendicular to the text direction
+        origin_x -= sin(angle) * BASELINE_OFFSET * font_size
+        origin_y += cos(angle) * BASELINE_OFFSET * font_size
+
+        matrix = [cos(angle), sin(angle), -sin(angle), cos(angle), origin_x, origin_y]
+
+        operations.append(f"{TEXT_LAYER_FONT_NAME} {format_number(font_size)} Tf")
+        operations.append(f"{format_number(horizontal_scaling)} Tz")
+        operations.append(" ".join(format_number(v) for v in matrix) + " Tm")
+        operations.append(f"<{encoded.hex()}> Tj")
+
+    operations += ["ET", "Q"]
+
+    return "\n".join(operations).encode("latin-1")
+
+
+def add_text_layer_to_page(pdf_page: PageObject, words: List[TextBlock]):
+    """
+    Adds an invisible text layer to a page in place
+    """
+    overlay = PageObject.create_blank_page(width=pdf_page.mediabox.width, height=pdf_page.mediabox.height)
+    overlay[NameObject("/Resources")] = DictionaryObject(
+        {NameObject("/Font"): DictionaryObject({NameObject(TEXT_LAYER_FONT_NAME): _text_layer_font()})}
+    )
+
+    content = DecodedStreamObject()
+    content.set_data(text_layer_operations(pdf_page, words))
+    overlay[NameObject("/Contents")] = content
+
+    pdf_page.merge_page(overlay)
+
+
+def make_searchable_pdf(document: "Document", provider_name: Optional[str] = None) -> "Document":
+    """
+    Returns a new document with an invisible text layer built from the word-level text
+    sidecar of `provider_name` (the first provider by default).
+
+    The text can then be searched and copied in any PDF viewer. Characters outside of the
+    Windows-1252 character set are replaced by "?". The rest of the document, including its
+    metadata, outline, form fields and annotations, is kept as is.
+    """
+    if not document.text_sidecars:
+        raise ValueError(f"Document {document} does not have text data. Try running `perform_text_extraction` first")
+
+    if provider_name is not None and provider_name not in document.text_sidecars:
+        raise ValueError(f"Document {document} does not have text data for provider {provider_name}")
+
+    reader = PdfReader(BytesIO(document.file_bytes))
+    writer = PdfWriter(clone_from=reader)
+
+    for page in document.pages:
+        pdf_page = writer.pages[page.page_number - 1]
+        words = page.get_text_blocks("word", provider_name)
+
+        if words:
+            add_text_layer_to_page(pdf_page, words)
+
+    output_stream = BytesIO()
+    writer.write(output_stream)
+
+    return document._with_file_bytes(output_stream.getvalue())
@@ -1,10 +1,11 @@
 import hashlib
 import tempfile
 from concurrent.futures import ThreadPoolExecutor, as_completed
+from datetime import datetime
 from io import BytesIO
 from os import PathLike
 from pathlib import Path
-from typing import Union
+from typing import Optional, Tuple, Union
 from urllib.parse import unquote
 
 import fsspec
@@ -13,6 +14,9 @@ from pdfplumber import PDF
 
 from docprompt._exec.ghostscript import compress_pdf_to_path
 from docprompt.schema.document import Document
+from docprompt.utils.encryption import EncryptedDocumentError, decrypt_pdf_bytes
+from docprompt.utils.image import get_image_mime_type, images_to_pdf_bytes, is_image
+from docprompt.utils.repair import RepairReport, repair_pdf_bytes
 
 
 def ensure_path(fp: Union[Path, PathLike]) -> Path:
@@ -51,12 +55,28 @@ def get_page_count(fd: Union[Path, PathLike, bytes]) -> int:
         return len(pdf.pages)
 
 
-def load_document(fp: Union[Path, PathLike, bytes], do_compress: bool = False, do_clean: bool = False) -> Document:
+def load_document(
+    fp: Union[Path, PathLike, bytes],
+    do_compress: bool = False,
+    do_clean: bool = False,
+    password: Optional[str] = None,
+    repair: bool = False,
+) -> Document:
     """
-    Loads a document from a file path
+    Loads a document from a file path or bytes. PNG, JPEG and TIFF images are
+    wrapped into a PDF at their original resolution.
+
+    Encrypted PDFs are decrypted with `password` (or the empty password), raising
+    `EncryptedDocumentError` if that fails.
+
+    If `repair` is set, anything that isn't an image is treated as a possibly damaged PDF and
+    fixed with `docprompt.utils.repair.repair_pdf_bytes` before loading. The report is
+    available as `Document.repair_report`.
     """
     if isinstance(fp, bytes):
         file_bytes = fp
+        file_name = f"PDF-{datetime.now().isoformat()}.pdf"
+        file_path = None
     else:
         if not isinstance(fp, Path):
             fp = Path(fp)
@@ -67,13 +87,23 @@ def load_document(fp: Union[Path, PathLike, bytes], do_compress: bool = False, d
         with open(fp, "rb") as f:
             file_bytes: bytes = f.read()
 
-    if not is_pdf(file_bytes):
-        raise ValueError("File is not a PDF")
+        file_name = unquote(fp.name)
+        file_path = str(fp)
+
+    source_mime_type = get_image_mime_type(file_bytes)
+    repair_report = None
+
+    if source_mime_type is not None:
+        file_bytes = images_to_pdf_bytes([file_bytes])
+        file_name = Path(file_name).with_suffix(".pdf").name
+    else:
+        source_mime_type = "application/pdf"
+        file_bytes, repair_report = _prepare_pdf_bytes(file_bytes, password, repair)
 
     if do_compress or do_clean:
-        with tempfile.TemporaryDirectory(f"_process_{fp.name}") as temp_dir:
+        with tempfile.TemporaryDirectory(f"_process_{file_name}") as temp_dir:
             temp_path = Path(temp_dir)
-            temp_file = temp_path / fp.name
+            temp_file = temp_path / file_name
 
             with temp_file.open("wb") as f:
                 f.write(file_bytes)
@@ -87,26 +117,60 @@ def load_document(fp: Union[Path, PathLike, bytes], do_compress: bool = False, d
                 # compress_pdf_to_path(temp_file, temp_path / "cleaned.pdf", clean=True)
                 # file_bytes = (temp_path / "cleaned.pdf").read_bytes()
 
-    return Document(name=unquote(fp.name), file_path=str(fp), file_bytes=file_bytes)
+    document = Document(name=file_name, file_path=file_path, file_bytes=file_bytes, source_mime_type=source_mime_type)
+    document._repair_report = repair_report
+
+    return document
+
+
+def _prepare_pdf_bytes(
+    file_bytes: bytes, password: Optional[str], repair: bool
+) -> Tuple[bytes, Optional[RepairReport]]:
+    """
+    Decrypts a PDF, repairing it first if `repair` is set, and returns it along with the repair report
+    """
+    if not repair:
+        if not is_pdf(file_bytes):
+            raise ValueError("File is not a PDF or a supported image")
+
+        return decrypt_pdf_bytes(file_bytes, password), None
+
+    # Damaged files may not be recognized as PDFs, e.g. if their header is missing or garbled,
+    # so anything that isn't an image goes through the repair pipeline
+    try:
+        file_bytes = decrypt_pdf_bytes(file_bytes, password)
+    except EncryptedDocumentError:
+        raise
+    except Exception:
+        pass  # Too damaged to check for encryption, so we'll try again after repairing
+
+    file_bytes, report = repair_pdf_bytes(file_bytes)
 
+    return decrypt_pdf_bytes(file_bytes, password), report
 
-def load_document_from_url(url: str, **kwargs):
+
+def load_document_from_url(url: str, password: Optional[str] = None, repair: bool = False, **kwargs):
     with fsspec.open(url, "rb") as f:
         file_bytes: bytes = f.read()
 
     file_name = unquote(url.split("/")[-1])
 
-    if not is_pdf(file_bytes):
-        raise ValueError("File is not a PDF")
+    if is_image(file_bytes):
+        return Document.from_image(file_bytes, name=file_name, file_path=url)
+
+    file_bytes, repair_report = _prepare_pdf_bytes(file_bytes, password, repair)
 
-    return Document(name=file_name, file_path=url, file_bytes=file_bytes)
+    document = Document(name=file_name, file_path=url, file_bytes=file_bytes)
+    document._repair_report = repair_report
+
+    return document
 
 
 def load_documents_from_urls(urls: list[str], max_workers: int = 5, **kwargs) -> list[Document]:
     documents = []
 
     with ThreadPoolExecutor(max_workers=max_workers) as executor:
-        future_to_url = {executor.submit(load_document_from_url, url): url for url in urls}
+        future_to_url = {executor.submit(load_document_from_url, url, **kwargs): url for url in urls}
         for future in as_completed(future_to_url):
             url = future_to_url[future]
             try:
@@ -129,3 +193,10 @@ def hash_from_bytes(byte_data: bytes) -> str:
         hash.update(mv[:n])
 
     return hash.hexdigest()
+
+
+def format_number(value: float) -> str:
+    """
+    Formats a number for a PDF content stream, with at most 4 decimals and no trailing zeros
+    """
+    return f"{value:.4f}".rstrip("0").rstrip(".") or "0"
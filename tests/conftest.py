@@ -0,0 +1,174 @@
+from io import BytesIO
+from typing import List, Optional, Tuple
+
+import pytest
+from pypdf import PdfWriter
+from pypdf.generic import (
+    ArrayObject,
+    DecodedStreamObject,
+    DictionaryObject,
+    FloatObject,
+    NameObject,
+    NumberObject,
+    TextStringObject,
+)
+
+from docprompt.schema.document import Document
+from docprompt.schema.layout import Geometry, NormBBox, TextBlock, TextSpan
+from docprompt.schema.operations import PageTextExtractionOutput
+
+
+def _text_font() -> DictionaryObject:
+    return DictionaryObject(
+        {
+            NameObject("/Type"): NameObject("/Font"),
+            NameObject("/Subtype"): NameObject("/Type1"),
+            NameObject("/BaseFont"): NameObject("/Helvetica"),
+        }
+    )
+
+
+@pytest.fixture
+def make_pdf():
+    """
+    Builds a PDF with one page per string, each drawn in Helvetica near the top-left corner
+    """
+
+    def make(page_texts: List[str], width: float = 612, height: float = 792) -> bytes:
+        writer = PdfWriter()
+
+        for text in page_texts:
+            page = writer.add_blank_page(width, height)
+            page[NameObject("/Resources")] = DictionaryObject(
+                {NameObject("/Font"): DictionaryObject({NameObject("/F1"): _text_font()})}
+            )
+
+            content = DecodedStreamObject()
+            content.set_data(f"BT /F1 24 Tf 72 {height - 100} Td ({text}) Tj ET".encode("latin-1"))
+            page[NameObject("/Contents")] = writer.add_object(content)
+
+        output_stream = BytesIO()
+        writer.write(output_stream)
+
+        return output_stream.getvalue()
+
+    return make
+
+
+def _widget(name: str, field_type: str, rect: Tuple[float, float, float, float], **entries) -> DictionaryObject:
+    widget = DictionaryObject(
+        {
+            NameObject("/Type"): NameObject("/Annot"),
+            NameObject("/Subtype"): NameObject("/Widget"),
+            NameObject("/FT"): NameObject(field_type),
+            NameObject("/T"): TextStringObject(name),
+            NameObject("/Rect"): ArrayObject([FloatObject(v) for v in rect]),
+            NameObject("/DA"): TextStringObject("/Helv 0 Tf 0 g"),
+        }
+    )
+    widget.update({NameObject(key): value for key, value in entries.items()})
+
+    return widget
+
+
+@pytest.fixture
+def make_form_pdf():
+    """
+    Builds a single page PDF with a text field 'name', a checkbox 'agree' exporting 'Yes' and a
+    choice field 'color' offering Red, Green and Blue
+    """
+
+    def make(outline: Optional[List[str]] = None) -> bytes:
+        writer = PdfWriter()
+        page = writer.add_blank_page(612, 792)
+
+        checkbox_appearance = DictionaryObject(
+            {
+                NameObject("/Yes"): writer.add_object(DecodedStreamObject()),
+                NameObject("/Off"): writer.add_object(DecodedStreamObject()),
+            }
+        )
+
+        widgets = [
+            _widget("name", "/Tx", (72, 700, 300, 720)),
+            _widget(
+                "agree",
+                "/Btn",
+                (72, 660, 86, 674),
+                **{
+                    "/V": NameObject("/Off"),
+                    "/AS": NameObject("/Off"),
+                    "/AP": DictionaryObject({NameObject("/N"): checkbox_appearance}),
+                },
+            ),
+            _widget(
+                "color",
+                "/Ch",
+                (72, 620, 200, 640),
+                **{
+                    "/Ff": NumberObject(1 << 17),  # Combo box
+                    "/Opt": ArrayObject([TextStringObject(option) for option in ["Red", "Green", "Blue"]]),
+                },
+            ),
+        ]
+        references = ArrayObject([writer.add_object(widget) for widget in widgets])
+
+        page[NameObject("/Annots")] = references
+        writer.root_object[NameObject("/AcroForm")] = writer.add_object(
+            DictionaryObject({NameObject("/Fields"): ArrayObject(references)})
+        )
+
+        for title in outline or []:
+            writer.add_outline_item(title, 0)
+
+        output_stream = BytesIO()
+        writer.write(output_stream)
+
+        return output_stream.getvalue()
+
+    return make
+
+
+@pytest.fixture
+def make_document(make_pdf):
+    def make(page_texts: List[str], name: str = "test.pdf") -> Document:
+        return Document(name=name, file_bytes=make_pdf(page_texts))
+
+    return make
+
+
+@pytest.fixture
+def make_page_text():
+    """
+    Builds a text extraction output from words and their boxes as (x0, top, x1, bottom), laid
+    out on a single line with spans into the page text
+    """
+
+    def make(
+        words: List[Tuple[str, Tuple[float, float, float, float]]], line_bbox: Optional[NormBBox] = None
+    ) -> PageTextExtractionOutput:
+        blocks = []
+        offset = 0
+
+        for text, (x0, top, x1, bottom) in words:
+            blocks.append(
+                TextBlock(
+                    text=text,
+                    type="word",
+                    geometry=Geometry(bounding_box=NormBBox(x0=x0, top=top, x1=x1, bottom=bottom)),
+                    text_spans=[TextSpan(start_index=offset, end_index=offset + len(text), level="page")],
+                )
+            )
+            offset += len(text) + 1
+
+        page_text = " ".join(text for text, _ in words)
+        line = TextBlock(
+            text=page_text,
+            type="line",
+            geometry=Geometry(bounding_box=line_bbox or NormBBox.combine(*[block.bounding_box for block in blocks])),
+            text_spans=[TextSpan(start_index=0, end_index=len(page_text), level="page")],
+        )
+
+        return PageTextExtractionOutput(text=page_text, words=blocks, lines=[line])
+
+    return make
@@ -0,0 +1,94 @@
+from io import BytesIO
+
+import pytest
+from pypdf import PdfReader, PdfWriter
+from pypdf.generic import ArrayObject, DictionaryObject, FloatObject, NameObject, TextStringObject
+
+from docprompt.schema.document import Document
+from docprompt.schema.layout import Geometry, NormBBox, TextBlock
+from docprompt.utils.annotations import DEFAULT_HIGHLIGHT_COLOR, annotate_document, get_annotations
+
+BBOX = NormBBox(x0=0.1, top=0.2, x1=0.4, bottom=0.25)
+
+
+def make_block(text: str, bbox: NormBBox = BBOX) -> TextBlock:
+    return TextBlock(text=text, type="word", geometry=Geometry(bounding_box=bbox))
+
+
+def assert_bbox(actual: NormBBox, expected: NormBBox, abs: float = 1e-4):
+    assert (actual.x0, actual.top, actual.x1, actual.bottom) == pytest.approx(
+        (expected.x0, expected.top, expected.x1, expected.bottom), abs=abs
+    )
+
+
+def test_highlight_round_trip(make_document):
+    document = make_document(["one", "two"])
+
+    annotated = annotate_document(document, {2: [make_block("Note")]}, author="Reviewer", include_text=True)
+
+    assert get_annotations(document.file_bytes) == []
+
+    (annotation,) = get_annotations(annotated.file_bytes)
+
+    assert annotation.type == "highlight"
+    assert annotation.page_number == 2
+    assert annotation.author == "Reviewer"
+    assert annotation.contents == "Note"
+    assert annotation.color == pytest.approx(DEFAULT_HIGHLIGHT_COLOR)
+    assert_bbox(annotation.bounding_box, BBOX)
+    assert len(annotation.bounding_polys) == 1
+
+
+def test_rectangle_round_trip(make_document):
+    annotated = annotate_document(
+        make_document(["one"]), {1: [make_block("a"), make_block("b")]}, annotation_type="rectangle", line_width=0
+    )
+
+    annotations = get_annotations(annotated.file_bytes, pages=[1])
+
+    assert [annotation.type for annotation in annotations] == ["rectangle", "rectangle"]
+    assert annotations[0].contents is None
+    assert_bbox(annotations[0].bounding_box, BBOX)
+
+
+def test_annotate_rejects_invalid_pages(make_document):
+    with pytest.raises(ValueError):
+        annotate_document(make_document(["one"]), {2: [make_block("a")]})
+
+
+def test_links(make_pdf):
+    writer = PdfWriter(clone_from=PdfReader(BytesIO(make_pdf(["one", "two"]))))
+    rect = ArrayObject([FloatObject(v) for v in (72, 692, 144, 716)])
+
+    internal = DictionaryObject(
+        {
+            NameObject("/Type"): NameObject("/Annot"),
+            NameObject("/Subtype"): NameObject("/Link"),
+            NameObject("/Rect"): rect,
+            NameObject("/Dest"): ArrayObject([writer.pages[1].indirect_reference, NameObject("/Fit")]),
+        }
+    )
+    external = DictionaryObject(
+        {
+            NameObject("/Type"): NameObject("/Annot"),
+            NameObject("/Subtype"): NameObject("/Link"),
+            NameObject("/Rect"): rect,
+            NameObject("/A"): DictionaryObject(
+                {
+                    NameObject("/S"): NameObject("/URI"),
+                    NameObject("/URI"): TextStringObject("https://example.com"),
+                }
+            ),
+        }
+    )
+    writer.pages[0][NameObject("/Annots")] = ArrayObject([writer.add_object(internal), writer.add_object(external)])
+
+    output_stream = BytesIO()
+    writer.write(output_stream)
+
+    annotations = Document(name="links.pdf", file_bytes=output_stream.getvalue()).annotations
+
+    assert [(annotation.destination_page, annotation.uri) for annotation in annotations] == [
+        (2, None),
+        (None, "https://example.com"),
+    ]
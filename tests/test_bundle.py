@@ -0,0 +1,110 @@
+import json
+import zipfile
+from io import BytesIO
+
+import pytest
+
+from docprompt.schema.document import Document
+from docprompt.utils.bundle import (
+    MANIFEST_PATH,
+    DocumentBundle,
+    InvalidBundleError,
+    load_bundle,
+    load_bundle_page_sidecar,
+    save_bundle,
+)
+
+
+@pytest.fixture
+def document(make_document, make_page_text):
+    document = make_document(["one", "two"])
+    document.text_sidecars = {
+        "provider/v1": {
+            1: make_page_text([("one", (0.1, 0.1, 0.2, 0.13))]),
+            2: make_page_text([("two", (0.1, 0.1, 0.2, 0.13))]),
+        },
+        "other": {2: make_page_text([("deux", (0.1, 0.1, 0.2, 0.13))])},
+    }
+
+    return document
+
+
+def test_round_trip(document, tmp_path):
+    path = tmp_path / "document.zip"
+
+    document.save_bundle(path)
+    loaded = Document.load_bundle(path)
+
+    assert loaded.name == document.name
+    assert loaded.file_bytes == document.file_bytes
+    assert loaded.text_sidecars == document.text_sidecars
+
+
+def test_round_trip_through_file_object(document):
+    buffer = BytesIO()
+
+    save_bundle(document, buffer)
+    buffer.seek(0)
+
+    assert load_bundle(buffer).text_sidecars == document.text_sidecars
+
+
+def test_read_single_page_sidecar(document, tmp_path):
+    path = tmp_path / "document.zip"
+    save_bundle(document, path)
+
+    assert load_bundle_page_sidecar(path, 2, "other").text == "deux"
+    assert load_bundle_page_sidecar(path, 1).text == "one"
+    assert load_bundle_page_sidecar(path, 1, "other") is None
+
+
+def test_cached_rasters_round_trip(document, tmp_path):
+    path = tmp_path / "document.zip"
+    document.pages[0]._raster_cache[(100, "png16m", "ghostscript")] = b"raster"
+
+    save_bundle(document, path, include_rasters=True)
+
+    with DocumentBundle(path) as bundle:
+        assert bundle.read_raster(1, 100) == b"raster"
+        assert bundle.read_raster(1, 100, rasterizer="pdfium") is None
+
+    assert load_bundle(path).pages[0]._raster_cache == {(100, "png16m", "ghostscript"): b"raster"}
+
+
+def test_rejects_tampered_document(document, tmp_path):
+    path = tmp_path / "document.zip"
+    save_bundle(document, path)
+
+    with zipfile.ZipFile(path) as bundle:
+        members = {name: bundle.read(name) for name in bundle.namelist()}
+
+    manifest = json.loads(members[MANIFEST_PATH])
+    manifest["document_hash"] = "0" * 32
+    members[MANIFEST_PATH] = json.dumps(manifest).encode()
+
+    with zipfile.ZipFile(path, "w") as bundle:
+        for name, data in members.items():
+            bundle.writestr(name, data)
+
+    with pytest.raises(InvalidBundleError):
+        load_bundle(path)
+
+    assert load_bundle(path, validate_hash=False).file_bytes == document.file_bytes
+
+
+def test_rejects_other_files(tmp_path):
+    path = tmp_path / "document.zip"
+    path.write_bytes(b"not a zip")
+
+    with pytest.raises(InvalidBundleError):
+        load_bundle(path)
+
+
+def test_rejects_newer_versions(tmp_path):
+    path = tmp_path / "document.zip"
+
+    with zipfile.ZipFile(path, "w") as bundle:
+        bundle.writestr(MANIFEST_PATH, json.dumps({"version": 99, "name": "x", "document_hash": "", "page_count": 1}))
+
+    with pytest.raises(InvalidBundleError):
+        load_bundle(path)
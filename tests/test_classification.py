@@ -0,0 +1,85 @@
+from io import BytesIO
+
+import pytest
+from PIL import Image
+from pypdf import PdfReader, PdfWriter
+from pypdf.generic import RectangleObject
+
+from docprompt.utils.classification import PageMetrics, classify_page_metrics, classify_pdf_pages
+from docprompt.utils.image import images_to_pdf_bytes
+
+
+def make_metrics(**kwargs) -> PageMetrics:
+    defaults = dict(
+        char_count=0,
+        image_count=0,
+        vector_count=0,
+        image_coverage=0.0,
+        glyph_coverage=0.0,
+        glyph_to_image_ratio=None,
+        text_quality=0.0,
+    )
+
+    return PageMetrics(**{**defaults, **kwargs})
+
+
+@pytest.mark.parametrize(
+    "metrics, label",
+    [
+        (make_metrics(), "blank"),
+        (make_metrics(char_count=500, glyph_coverage=0.2, text_quality=1.0), "born_digital"),
+        (make_metrics(image_count=1, image_coverage=1.0), "scanned"),
+        (
+            make_metrics(
+                char_count=500, image_count=1, image_coverage=1.0, glyph_to_image_ratio=0.2, text_quality=0.99
+            ),
+            "scanned_with_text_layer",
+        ),
+        (
+            make_metrics(char_count=500, image_count=1, image_coverage=1.0, glyph_to_image_ratio=0.2, text_quality=0.3),
+            "scanned_with_bad_text_layer",
+        ),
+    ],
+)
+def test_classify_page_metrics(metrics, label):
+    classification = classify_page_metrics(1, metrics)
+
+    assert classification.label == label
+    assert 0.5 <= classification.confidence <= 1
+
+
+def make_image_pdf() -> bytes:
+    output = BytesIO()
+    Image.new("L", (200, 100), 128).save(output, "PNG")
+
+    return images_to_pdf_bytes([output.getvalue()], dpi=72)
+
+
+def test_classify_pdf_pages(make_pdf):
+    classifications = classify_pdf_pages(make_pdf(["Some born digital text"]))
+
+    assert list(classifications) == [1]
+    assert classifications[1].label == "born_digital"
+    assert classify_pdf_pages(make_image_pdf())[1].label == "scanned"
+
+
+def test_image_coverage_is_measured_against_the_crop_box():
+    writer = PdfWriter(clone_from=PdfReader(BytesIO(make_image_pdf())))
+
+    # The image fills the visible quarter of a page whose media box is twice as large each way
+    writer.pages[0].mediabox = RectangleObject((0, 0, 400, 200))
+    writer.pages[0].cropbox = RectangleObject((0, 0, 200, 100))
+
+    output_stream = BytesIO()
+    writer.write(output_stream)
+
+    classification = classify_pdf_pages(output_stream.getvalue())[1]
+
+    assert classification.metrics.image_coverage == pytest.approx(1.0)
+    assert classification.label == "scanned"
+
+
+@pytest.mark.parametrize("page_number", [0, -1, 3])
+def test_classify_pdf_pages_rejects_invalid_pages(make_pdf, page_number):
+    with pytest.raises(ValueError):
+        classify_pdf_pages(make_pdf(["One", "Two"]), [page_number])
@@ -0,0 +1,100 @@
+from docprompt.schema.layout import NormBBox
+from docprompt.utils.diff import DiffChange, DiffRegion, _detect_moves, align_pages
+
+INTRODUCTION = "this agreement is made between the parties named below".split()
+TERMS = "the supplier shall deliver the goods within thirty days".split()
+SIGNATURES = "signed by the authorized representatives of each party".split()
+APPENDIX = "appendix listing every product covered by this contract".split()
+
+
+def get_pairs(alignment):
+    return [(page.page_number_a, page.page_number_b) for page in alignment]
+
+
+def test_align_identical_pages():
+    alignment = align_pages({1: INTRODUCTION, 2: TERMS}, {1: INTRODUCTION, 2: TERMS})
+
+    assert get_pairs(alignment) == [(1, 1), (2, 2)]
+    assert all(page.similarity == 1.0 for page in alignment)
+
+
+def test_align_inserted_page():
+    alignment = align_pages({1: INTRODUCTION, 2: TERMS}, {1: INTRODUCTION, 2: APPENDIX, 3: TERMS})
+
+    assert get_pairs(alignment) == [(1, 1), (None, 2), (2, 3)]
+
+
+def test_align_deleted_page():
+    alignment = align_pages({1: INTRODUCTION, 2: TERMS, 3: SIGNATURES}, {1: INTRODUCTION, 2: SIGNATURES})
+
+    assert get_pairs(alignment) == [(1, 1), (2, None), (3, 2)]
+
+
+def test_align_edited_page():
+    edited_terms = TERMS[:-2] + ["sixty", "days"]
+
+    alignment = align_pages({1: INTRODUCTION, 2: TERMS}, {1: INTRODUCTION, 2: edited_terms})
+
+    assert get_pairs(alignment) == [(1, 1), (2, 2)]
+    assert 0 < alignment[1].similarity < 1
+
+
+def test_dissimilar_pages_are_not_aligned():
+    alignment = align_pages({1: INTRODUCTION}, {1: APPENDIX})
+
+    assert set(get_pairs(alignment)) == {(1, None), (None, 1)}
+
+
+def make_change(type, text, page_number=1):
+    region = DiffRegion(page_number=page_number, bounding_box=NormBBox(x0=0.1, top=0.1, x1=0.5, bottom=0.12))
+
+    return DiffChange(
+        type=type,
+        text=text,
+        regions_a=[region] if type == "deletion" else [],
+        regions_b=[region] if type == "insertion" else [],
+    )
+
+
+def test_detect_moves_pairs_deleted_and_inserted_runs():
+    deletion = make_change("deletion", "within thirty days", page_number=1)
+    insertion = make_change("insertion", "within thirty days", page_number=2)
+    other = make_change("insertion", "sixty")
+
+    changes = _detect_moves([deletion, other, insertion], ignore_case=False, min_move_words=3)
+
+    assert [change.type for change in changes] == ["move", "insertion"]
+    assert changes[0].regions_a == deletion.regions_a
+    assert changes[0].regions_b == insertion.regions_b
+
+
+def test_detect_moves_ignores_short_runs():
+    changes = [make_change("deletion", "thirty days"), make_change("insertion", "thirty days")]
+
+    assert [change.type for change in _detect_moves(changes, ignore_case=False, min_move_words=3)] == [
+        "deletion",
+        "insertion",
+    ]
+
+
+def test_detect_moves_respects_case():
+    changes = [make_change("deletion", "Within Thirty Days"), make_change("insertion", "within thirty days")]
+
+    assert [change.type for change in _detect_moves(changes, ignore_case=False, min_move_words=3)] == [
+        "deletion",
+        "insertion",
+    ]
+    assert [change.type for change in _detect_moves(changes, ignore_case=True, min_move_words=3)] == ["move"]
+
+
+def test_detect_moves_pairs_each_insertion_once():
+    changes = [
+        make_change("deletion", "within thirty days"),
+        make_change("deletion", "within thirty days"),
+        make_change("insertion", "within thirty days"),
+    ]
+
+    assert [change.type for change in _detect_moves(changes, ignore_case=False, min_move_words=3)] == [
+        "move",
+        "deletion",
+    ]
@@ -0,0 +1,171 @@
+from io import BytesIO
+
+import pytest
+from PIL import Image
+from pypdf import PdfReader, PdfWriter
+
+from docprompt.rasterizers import BaseRasterizer, Raster
+from docprompt.schema.document import Document, DocumentPage
+from docprompt.schema.operations import PageTextExtractionOutput
+
+
+@pytest.fixture
+def document(make_document):
+    document = make_document(["one", "two", "three"])
+    document.text_sidecars = {
+        "provider": {
+            page_number: PageTextExtractionOutput(text=text)
+            for page_number, text in enumerate(["one", "two", "three"], start=1)
+        }
+    }
+
+    return document
+
+
+def get_sidecar_texts(document: Document, provider_name: str = "provider"):
+    return {page_number: page_text.text for page_number, page_text in document.text_sidecars[provider_name].items()}
+
+
+def test_pages_are_one_indexed(document):
+    assert [page.page_number for page in document.pages] == [1, 2, 3]
+    assert document[0].page_number == 1
+    assert document[-1].text == "three"
+
+
+def test_get_page(document):
+    assert document.get_page(2) is document.pages[1]
+
+    for page_number in [0, 4]:
+        with pytest.raises(ValueError):
+            document.get_page(page_number)
+
+
+def test_page_dimensions(make_pdf):
+    writer = PdfWriter(clone_from=PdfReader(BytesIO(make_pdf(["upright", "sideways"]))))
+    writer.pages[1].rotate(90)
+
+    output_stream = BytesIO()
+    writer.write(output_stream)
+
+    upright, sideways = Document(name="rotated.pdf", file_bytes=output_stream.getvalue()).pages
+
+    assert (upright.width, upright.height, upright.rotation) == (612, 792, 0)
+    assert (sideways.width, sideways.height, sideways.rotation) == (792, 612, 90)
+    assert sideways.get_render_size(144) == (1584, 1224)
+
+
+def test_page_text_data(document):
+    page = document.get_page(2)
+
+    assert page.text == "two"
+    assert page.get_text_data("provider").text == "two"
+    assert page.get_text_data("missing") is None
+    assert page.label == "2"
+
+    document.text_sidecars = {}
+
+    assert page.text_data is None
+    assert page.text == ""
+
+
+def test_page_rasters_are_cached(document, monkeypatch):
+    calls = []
+
+    def rasterize_page(self, page_number, dpi, device, **kwargs):
+        calls.append((page_number, dpi, device))
+        output = BytesIO()
+        Image.new("L", (10, 10)).save(output, "PNG")
+
+        return output.getvalue()
+
+    monkeypatch.setattr(Document, "rasterize_page", rasterize_page)
+
+    page = document.get_page(3)
+
+    assert page.rasterize(dpi=100) == page.rasterize(dpi=100)
+    assert page.get_image(dpi=50, device="pnggray").size == (10, 10)
+    assert calls == [(3, 100, "png16m"), (3, 50, "pnggray")]
+
+
+class LetterRasterizer(BaseRasterizer):
+    """
+    Renders every page as a blank US Letter image at the requested DPI
+    """
+
+    name = "letter"
+
+    def _rasterize(self, fp, pages, *, dpi, device, downscale_factor, quality):
+        rasters = {}
+
+        for page_number in pages:
+            output = BytesIO()
+            Image.new("L", (round(8.5 * dpi), 11 * dpi)).save(output, "PNG")
+            rasters[page_number] = output.getvalue()
+
+        return rasters
+
+
+def test_rasterize_page_returns_bytes(document):
+    data = document.rasterize_page(2, dpi=72, rasterizer=LetterRasterizer(), cache=False)
+
+    assert isinstance(data, bytes)
+    assert Image.open(BytesIO(data)).size == (612, 792)
+
+
+def test_rasterize_to_size_returns_rasters(document):
+    raster = document.rasterize_page_to_size(2, max_width=1000, rasterizer=LetterRasterizer(), cache=False)
+
+    assert isinstance(raster, Raster)
+    assert (raster.page_number, raster.dpi, raster.width) == (2, 117, 994)
+
+    rasters = document.rasterize_pdf_to_size(max_height=792, pages=[1, 3], rasterizer=LetterRasterizer(), cache=False)
+
+    assert sorted(rasters) == [1, 3]
+    assert all(raster.dpi == 72 and raster.height == 792 for raster in rasters.values())
+
+    with pytest.raises(ValueError):
+        document.rasterize_page_to_size(4, max_width=1000, rasterizer=LetterRasterizer(), cache=False)
+
+
+def test_slicing_renumbers_sidecars(document):
+    sliced = document[1:]
+
+    assert sliced.num_pages == 2
+    assert get_sidecar_texts(sliced) == {1: "two", 2: "three"}
+
+
+def test_reorder_renumbers_sidecars(document):
+    reordered = document.reorder([3, 1, 2])
+
+    assert get_sidecar_texts(reordered) == {1: "three", 2: "one", 3: "two"}
+
+
+def test_reorder_requires_every_page_once(document):
+    with pytest.raises(ValueError):
+        document.reorder([1, 1, 2])
+
+
+def test_delete_pages_renumbers_sidecars(document):
+    assert get_sidecar_texts(document.delete_pages([2])) == {1: "one", 2: "three"}
+
+
+def test_from_pages_skips_pages_without_sidecars(document, make_document):
+    other = make_document(["four"])
+
+    combined = Document.from_pages([other.pages[0], document.pages[2], document.pages[0]])
+
+    assert combined.num_pages == 3
+    assert get_sidecar_texts(combined) == {2: "three", 3: "one"}
+
+
+def test_concat_keeps_each_documents_sidecars(document):
+    combined = Document.concat([document, document[:1]])
+
+    assert get_sidecar_texts(combined) == {1: "one", 2: "two", 3: "three", 4: "one"}
+
+
+def test_sidecars_are_copied(document):
+    reordered = document.reorder([2, 1, 3])
+    reordered.text_sidecars["provider"][1].text = "changed"
+
+    assert document.text_sidecars["provider"][2].text == "two"
@@ -0,0 +1,54 @@
+from io import BytesIO
+
+import pytest
+from PIL import Image
+from pypdf import PdfReader, PdfWriter
+from pypdf.generic import FloatObject, NameObject
+
+from docprompt.utils.embedded_images import extract_page_images
+from docprompt.utils.image import images_to_pdf_bytes
+
+
+def make_image_pdf(rotation: int = 0, user_unit: float = 1.0) -> bytes:
+    """
+    Builds a page showing a 200x100 pixel image at 100 DPI, i.e. drawn 2 inches wide and 1 inch tall
+    """
+    output = BytesIO()
+    Image.new("RGB", (200, 100), "red").save(output, "PNG")
+
+    writer = PdfWriter(clone_from=PdfReader(BytesIO(images_to_pdf_bytes([output.getvalue()], dpi=100))))
+
+    if rotation:
+        writer.pages[0].rotate(rotation)
+
+    if user_unit != 1.0:
+        writer.pages[0][NameObject("/UserUnit")] = FloatObject(user_unit)
+
+    output_stream = BytesIO()
+    writer.write(output_stream)
+
+    return output_stream.getvalue()
+
+
+@pytest.mark.parametrize("rotation", [0, 90, 180, 270])
+def test_dpi_is_measured_along_the_image_axes(rotation):
+    images = extract_page_images(make_image_pdf(rotation), 1)
+
+    assert len(images) == 1
+    assert (images[0].width, images[0].height) == (200, 100)
+    assert images[0].dpi == pytest.approx((100, 100))
+
+    bbox = images[0].bounding_box
+
+    assert (bbox.x0, bbox.top, bbox.x1, bbox.bottom) == pytest.approx((0, 0, 1, 1))
+
+
+def test_dpi_follows_the_user_unit():
+    images = extract_page_images(make_image_pdf(user_unit=2.0), 1)
+
+    assert images[0].dpi == pytest.approx((50, 50))
+
+
+def test_invalid_page_number():
+    with pytest.raises(ValueError):
+        extract_page_images(make_image_pdf(), 2)
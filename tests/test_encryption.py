@@ -0,0 +1,64 @@
+from io import BytesIO
+
+import pytest
+from pypdf import PdfReader, PdfWriter
+
+from docprompt.schema.document import Document
+from docprompt.utils.encryption import EncryptedDocumentError, decrypt_pdf_bytes, is_encrypted_pdf
+
+
+def encrypt(file_bytes: bytes, user_password: str, owner_password: str) -> bytes:
+    writer = PdfWriter(clone_from=PdfReader(BytesIO(file_bytes)))
+    writer.encrypt(user_password, owner_password, algorithm="RC4-128")  # RC4 doesn't need 'cryptography'
+
+    output_stream = BytesIO()
+    writer.write(output_stream)
+
+    return output_stream.getvalue()
+
+
+def test_unencrypted_pdf_is_returned_as_is(make_pdf):
+    file_bytes = make_pdf(["Public"])
+
+    assert not is_encrypted_pdf(file_bytes)
+    assert decrypt_pdf_bytes(file_bytes) is file_bytes
+
+
+def test_right_password_gives_readable_bytes(make_pdf):
+    file_bytes = encrypt(make_pdf(["Secret"]), "user", "owner")
+
+    assert is_encrypted_pdf(file_bytes)
+
+    reader = PdfReader(BytesIO(decrypt_pdf_bytes(file_bytes, "user")))
+
+    assert not reader.is_encrypted
+    assert "Secret" in reader.pages[0].extract_text()
+
+
+@pytest.mark.parametrize("password", [None, "wrong"])
+def test_wrong_or_missing_password_raises(make_pdf, password):
+    file_bytes = encrypt(make_pdf(["Secret"]), "user", "owner")
+
+    with pytest.raises(EncryptedDocumentError):
+        decrypt_pdf_bytes(file_bytes, password)
+
+
+def test_owner_password_only_opens_without_a_password(make_pdf):
+    file_bytes = encrypt(make_pdf(["Restricted"]), "", "owner")
+
+    reader = PdfReader(BytesIO(decrypt_pdf_bytes(file_bytes)))
+
+    assert not reader.is_encrypted
+    assert "Restricted" in reader.pages[0].extract_text()
+
+
+def test_document_from_bytes_decrypts(make_pdf):
+    file_bytes = encrypt(make_pdf(["Secret"]), "user", "owner")
+
+    with pytest.raises(EncryptedDocumentError):
+        Document.from_bytes(file_bytes)
+
+    document = Document.from_bytes(file_bytes, password="user")
+
+    assert not document.is_encrypted
+    assert document.num_pages == 1
@@ -0,0 +1,127 @@
+from io import BytesIO
+from typing import List, Optional
+
+from PIL import Image
+
+from docprompt.schema.document import Document
+from docprompt.utils.fingerprint import (
+    FINGERPRINT_DPI,
+    DocumentFingerprint,
+    PageFingerprint,
+    compute_text_hash,
+    find_near_duplicates,
+    fingerprint_document,
+    image_similarity,
+    text_similarity,
+)
+
+IMAGE_HASH = "f0f0f0f0f0f0f0f0"
+
+TEXT = "the quick brown fox jumps over the lazy dog while the cat sleeps in the warm afternoon sun"
+OTHER_TEXT = "an entirely different page about quarterly revenue figures and the outlook for next year"
+
+
+def flip_bits(image_hash: str, count: int) -> str:
+    return f"{int(image_hash, 16) ^ ((1 << count) - 1):016x}"
+
+
+def make_page(page_number: int, image_hash: str, text: Optional[str] = None, is_blank: bool = False):
+    return PageFingerprint(
+        page_number=page_number,
+        image_hash=image_hash,
+        text_hash=compute_text_hash(text) if text else None,
+        is_blank=is_blank,
+    )
+
+
+def make_document(name: str, pages: List[PageFingerprint]) -> DocumentFingerprint:
+    return DocumentFingerprint(name=name, document_hash=name, pages=pages)
+
+
+def test_similarity_scores():
+    a, b = make_page(1, IMAGE_HASH, TEXT), make_page(1, flip_bits(IMAGE_HASH, 4), TEXT.upper() + "!")
+
+    assert image_similarity(a, b) == 1 - 4 / 64
+    assert text_similarity(a, b) == 1.0
+    assert text_similarity(a, make_page(1, IMAGE_HASH)) is None
+
+
+def test_pages_with_similar_images_are_grouped():
+    duplicates = find_near_duplicates(
+        [
+            make_document("a", [make_page(1, IMAGE_HASH)]),
+            make_document("b", [make_page(1, flip_bits(IMAGE_HASH, 3))]),
+            make_document("c", [make_page(1, flip_bits(IMAGE_HASH, 20))]),
+        ]
+    )
+
+    assert duplicates.page_groups == [[(0, 1), (1, 1)]]
+    assert duplicates.document_groups == [[0, 1]]
+
+
+def test_text_decides_for_pages_with_text():
+    duplicates = find_near_duplicates(
+        [
+            make_document("a", [make_page(1, IMAGE_HASH, TEXT)]),
+            make_document("b", [make_page(1, IMAGE_HASH, OTHER_TEXT)]),
+            make_document("c", [make_page(1, flip_bits(IMAGE_HASH, 30), TEXT)]),
+        ]
+    )
+
+    assert duplicates.page_groups == [[(0, 1), (2, 1)]]
+
+
+def test_blank_pages_are_never_matched():
+    duplicates = find_near_duplicates(
+        [
+            make_document("a", [make_page(1, IMAGE_HASH), make_page(2, "0" * 16, is_blank=True)]),
+            make_document("b", [make_page(1, IMAGE_HASH), make_page(2, "0" * 16, is_blank=True)]),
+        ]
+    )
+
+    assert duplicates.page_groups == [[(0, 1), (1, 1)]]
+    assert duplicates.document_groups == [[0, 1]]
+
+
+def test_documents_need_enough_matching_pages():
+    shared = make_page(1, IMAGE_HASH, TEXT)
+
+    duplicates = find_near_duplicates(
+        [
+            make_document("a", [shared]),
+            make_document("b", [shared, make_page(2, flip_bits(IMAGE_HASH, 30), OTHER_TEXT)]),
+        ]
+    )
+
+    assert duplicates.page_groups == [[(0, 1), (1, 1)]]
+    assert duplicates.document_groups == []
+
+    identical = find_near_duplicates([make_document("a", [shared]), make_document("b", [shared])])
+
+    assert identical.document_groups == [[0, 1]]
+
+
+def test_pages_within_a_document_are_grouped():
+    duplicates = find_near_duplicates([make_document("a", [make_page(1, IMAGE_HASH), make_page(2, IMAGE_HASH)])])
+
+    assert duplicates.page_groups == [[(0, 1), (0, 2)]]
+    assert duplicates.document_groups == []
+
+
+def test_fingerprint_document_rasterizes_once(make_document, monkeypatch):
+    calls = []
+
+    def rasterize_pdf(self, dpi, device, **kwargs):
+        calls.append((dpi, device))
+        output = BytesIO()
+        Image.new("L", (30, 40), 255).save(output, "PNG")
+
+        return {page_number: output.getvalue() for page_number in range(1, self.num_pages + 1)}
+
+    monkeypatch.setattr(Document, "rasterize_pdf", rasterize_pdf)
+
+    fingerprint = fingerprint_document(make_document(["One", "Two", "Three"]))
+
+    assert calls == [(FINGERPRINT_DPI, "pnggray")]
+    assert [page.page_number for page in fingerprint.pages] == [1, 2, 3]
+    assert all(page.is_blank for page in fingerprint.pages)
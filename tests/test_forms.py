@@ -0,0 +1,60 @@
+import pytest
+
+from docprompt.schema.document import Document
+from docprompt.utils.forms import fill_form, get_form_fields
+
+
+@pytest.fixture
+def form_document(make_form_pdf):
+    return Document(name="form.pdf", file_bytes=make_form_pdf())
+
+
+def get_values(document: Document):
+    return {field.name: field.value for field in get_form_fields(document.file_bytes)}
+
+
+def test_get_form_fields(form_document):
+    fields = {field.name: field for field in get_form_fields(form_document.file_bytes)}
+
+    assert {name: field.type for name, field in fields.items()} == {
+        "name": "text",
+        "agree": "checkbox",
+        "color": "choice",
+    }
+    assert fields["agree"].options == ["Yes"]
+    assert fields["color"].options == ["Red", "Green", "Blue"]
+    assert fields["name"].page_number == 1
+    assert fields["name"].bounding_box.x0 == pytest.approx(72 / 612)
+    assert fields["name"].bounding_box.top == pytest.approx(72 / 792)
+
+
+def test_fill_form_round_trip(form_document):
+    filled = fill_form(form_document, {"name": "Jane Doe", "agree": True, "color": "Green"})
+
+    assert get_values(form_document) == {"name": None, "agree": False, "color": None}
+    assert get_values(filled) == {"name": "Jane Doe", "agree": True, "color": "Green"}
+
+    unchecked = fill_form(filled, {"agree": False})
+
+    assert get_values(unchecked) == {"name": "Jane Doe", "agree": False, "color": "Green"}
+
+
+@pytest.mark.parametrize(
+    "values",
+    [
+        {"missing": "value"},
+        {"color": "Purple"},
+        {"color": ["Red", "Purple"]},
+        {"agree": "yes"},
+        {"name": True},
+        {"name": ["a", "b"]},
+    ],
+)
+def test_fill_form_rejects_invalid_values(form_document, values):
+    with pytest.raises(ValueError):
+        fill_form(form_document, values)
+
+
+def test_fill_form_without_a_form(make_document):
+    with pytest.raises(ValueError):
+        fill_form(make_document(["No form here"]), {"name": "Jane Doe"})
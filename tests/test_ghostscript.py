@@ -0,0 +1,185 @@
+from subprocess import CompletedProcess
+
+import pytest
+
+from docprompt._exec import ghostscript
+from docprompt._exec.ghostscript import (
+    PNG_SIGNATURE,
+    GhostscriptError,
+    GhostscriptTimeoutError,
+    _set_page_context,
+    classify_message,
+    group_page_runs,
+    parse_stderr,
+    rasterize_pages_to_bytes,
+    split_images,
+    split_jpeg_images,
+    split_png_images,
+)
+
+
+def make_segment(marker: int, payload: bytes) -> bytes:
+    return bytes([0xFF, marker]) + (len(payload) + 2).to_bytes(2, "big") + payload
+
+
+def make_jpeg(scan_data: bytes = b"\x12\x34", thumbnail: bytes = b"") -> bytes:
+    """
+    Builds the marker structure of a JPEG, which is all the splitter looks at
+    """
+    return (
+        b"\xff\xd8"
+        + make_segment(0xE1, b"Exif\x00\x00" + thumbnail)
+        + make_segment(0xDB, b"\x00" * 65)
+        + make_segment(0xDA, b"\x01\x01\x00\x00\x3f\x00")
+        + scan_data
+        + b"\xff\xd9"
+    )
+
+
+def test_split_jpeg_images():
+    images = [make_jpeg(b"\x01\x02"), make_jpeg(b"\x03\x04\x05")]
+
+    assert split_jpeg_images(b"".join(images)) == images
+
+
+def test_split_jpeg_images_with_embedded_thumbnail():
+    images = [make_jpeg(thumbnail=make_jpeg()), make_jpeg()]
+
+    assert split_jpeg_images(b"".join(images)) == images
+
+
+def test_split_jpeg_images_with_stuffed_bytes_and_restart_markers():
+    images = [make_jpeg(b"\x01\xff\x00\x02\xff\xd0\x03\xff\x00"), make_jpeg()]
+
+    assert split_jpeg_images(b"".join(images)) == images
+
+
+def test_split_png_images():
+    images = [PNG_SIGNATURE + b"first", PNG_SIGNATURE + b"second"]
+
+    assert split_png_images(b"".join(images)) == images
+    assert split_png_images(b"") == []
+
+
+def test_split_images_by_device():
+    jpeg = make_jpeg()
+
+    assert split_images(jpeg + jpeg, "jpeggray") == [jpeg, jpeg]
+    assert split_images(PNG_SIGNATURE * 2, "png16m") == [PNG_SIGNATURE, PNG_SIGNATURE]
+
+    with pytest.raises(ValueError):
+        split_images(b"", "tiffg4")
+
+
+def test_group_page_runs():
+    assert group_page_runs([5, 1, 2, 3, 8, 7, 3]) == [(1, 3), (5, 5), (7, 8)]
+    assert group_page_runs([4]) == [(4, 4)]
+    assert group_page_runs([]) == []
+
+
+def test_rasterize_pages_runs_ghostscript_once_per_run(monkeypatch):
+    runs = []
+
+    def rasterize_pdf_to_bytes(fp, *, first_page, last_page, **kwargs):
+        runs.append((first_page, last_page))
+
+        return {page_number: b"page %d" % page_number for page_number in range(first_page, last_page + 1)}
+
+    monkeypatch.setattr(ghostscript, "rasterize_pdf_to_bytes", rasterize_pdf_to_bytes)
+
+    images = rasterize_pages_to_bytes("document.pdf", [9, 2, 3, 4])
+
+    assert runs == [(2, 4), (9, 9)]
+    assert images == {2: b"page 2", 3: b"page 3", 4: b"page 4", 9: b"page 9"}
+
+
+def test_rasterize_pages_rejects_invalid_pages():
+    with pytest.raises(ValueError):
+        rasterize_pages_to_bytes("document.pdf", [0, 1])
+
+
+def make_process(stderr: bytes, stdout: bytes = b"", returncode: int = 1) -> CompletedProcess:
+    return CompletedProcess(args=["gs"], returncode=returncode, stdout=stdout, stderr=stderr)
+
+
+def test_parse_stderr_strips_decoration_and_duplicates():
+    stderr = (
+        b"   **** Error: xref table is damaged\n"
+        b"   **** Error: xref table is damaged\n"
+        b"\n"
+        b"   Output may be incorrect.\n"
+    )
+
+    assert parse_stderr(stderr) == ["Error: xref table is damaged", "Output may be incorrect."]
+    assert parse_stderr(None) == []
+
+
+@pytest.mark.parametrize(
+    "message, category",
+    [
+        ("Can't find (or can't open) font file /usr/share/fonts/Arial.pfb.", "missing_font"),
+        ("Substituting font Helvetica for ArialMT.", "missing_font"),
+        ("Error:  An error occurred while reading an XREF table.", "damaged_xref"),
+        ("The file has been damaged.  This may have been caused", "damaged_xref"),
+        ("Error: /VMerror in --showpage--", "out_of_memory"),
+        ("Error: /invalidfileaccess in --file--", "security"),
+        ("This file requires a password for access.", "security"),
+        ("Error: Unsupported JBIG2 global stream", "unsupported_feature"),
+        ("GPL Ghostscript 10.02.1: Unrecoverable error, exit code 1", "unknown"),
+    ],
+)
+def test_classify_message(message, category):
+    assert classify_message(message).category == category
+
+
+def test_classify_message_finds_page_number():
+    assert classify_message("Error reading page 12 of the document").page_number == 12
+    assert classify_message("Error: /undefined in --run--").page_number is None
+
+
+def test_error_reports_the_first_known_cause():
+    error = GhostscriptError(
+        "Ghostscript failed",
+        make_process(
+            b"GPL Ghostscript 10.02.1: Unrecoverable error, exit code 1\n"
+            b"   **** Error: can't find (or can't open) font file Foo on page 3\n"
+        ),
+    )
+
+    assert error.category == "missing_font"
+    assert error.page_number == 3
+    assert error.output_produced is False
+    assert str(error) == "Ghostscript failed on page 3: Error: can't find (or can't open) font file Foo on page 3"
+
+
+def test_error_without_stderr():
+    error = GhostscriptError("Ghostscript failed", make_process(b"", returncode=2))
+
+    assert error.category == "unknown"
+    assert error.primary_issue is None
+    assert str(error) == "Ghostscript failed (exit code 2)"
+
+
+def test_timeout_error_category():
+    error = GhostscriptTimeoutError("Ghostscript timed out", make_process(b"Error: /VMerror"), 5)
+
+    assert error.category == "timeout"
+    assert error.timeout == 5
+
+
+def test_page_context_from_partial_output():
+    process = make_process(b"Error: /syntaxerror in --token--", stdout=PNG_SIGNATURE * 2)
+
+    error = _set_page_context(GhostscriptError("Ghostscript failed", process), "%stdout", "png16m", 4, 10)
+
+    assert error.page_number == 6
+    assert error.output_produced is True
+
+
+def test_page_context_after_every_page_was_written():
+    process = make_process(b"Error: /ioerror", stdout=PNG_SIGNATURE * 2)
+
+    error = _set_page_context(GhostscriptError("Ghostscript failed", process), "%stdout", "png16m", 1, 2)
+
+    assert error.page_number is None
+    assert error.output_produced is True
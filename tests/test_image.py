@@ -0,0 +1,72 @@
+from io import BytesIO
+
+import pytest
+from PIL import Image
+from pypdf import PdfReader
+
+from docprompt.utils.image import images_to_pdf_bytes, open_image_frames
+
+
+def encode(image: Image.Image, image_format: str, **kwargs) -> bytes:
+    output = BytesIO()
+    image.save(output, image_format, **kwargs)
+
+    return output.getvalue()
+
+
+def get_page_sizes(file_bytes: bytes):
+    return [(float(page.mediabox.width), float(page.mediabox.height)) for page in PdfReader(BytesIO(file_bytes)).pages]
+
+
+def get_image_filter(file_bytes: bytes) -> str:
+    page = PdfReader(BytesIO(file_bytes)).pages[0]
+
+    return page["/Resources"]["/XObject"]["/Im0"].get_object()["/Filter"]
+
+
+@pytest.mark.parametrize(
+    "dpi, override, size",
+    [
+        ((100, 100), None, (144, 72)),
+        ((300, 300), None, (48, 24)),
+        ((200, 100), None, (72, 72)),  # Fax resolutions keep each axis to scale
+        ((300, 300), 100, (144, 72)),
+    ],
+)
+def test_page_size_follows_the_image_resolution(dpi, override, size):
+    png = encode(Image.new("RGB", (200, 100), "white"), "PNG", dpi=dpi)
+
+    assert get_page_sizes(images_to_pdf_bytes([png], dpi=override)) == [pytest.approx(size, abs=0.01)]
+
+
+def test_images_without_a_resolution_use_the_default():
+    png = encode(Image.new("L", (400, 200), "white"), "PNG")
+
+    assert get_page_sizes(images_to_pdf_bytes([png])) == [pytest.approx((144, 72), abs=0.01)]
+
+
+def test_multi_page_tiff_has_a_page_per_frame():
+    frames = [Image.new("L", (100, 100), shade) for shade in (0, 128, 255)]
+    tiff = encode(frames[0], "TIFF", save_all=True, append_images=frames[1:], dpi=(100, 100))
+
+    assert len(open_image_frames(tiff)) == 3
+    assert get_page_sizes(images_to_pdf_bytes([tiff])) == [pytest.approx((72, 72), abs=0.01)] * 3
+
+
+def test_jpeg_is_embedded_as_is():
+    jpeg = encode(Image.new("RGB", (100, 100), "red"), "JPEG", dpi=(100, 100))
+
+    assert get_image_filter(images_to_pdf_bytes([jpeg])) == "/DCTDecode"
+
+
+def test_mpo_photo_is_a_single_jpeg_page():
+    photo, preview = Image.new("RGB", (200, 100), "red"), Image.new("RGB", (40, 20), "blue")
+    mpo = encode(photo, "MPO", save_all=True, append_images=[preview], dpi=(100, 100))
+
+    assert Image.open(BytesIO(mpo)).n_frames == 2
+    assert len(open_image_frames(mpo)) == 1
+
+    file_bytes = images_to_pdf_bytes([mpo])
+
+    assert get_page_sizes(file_bytes) == [pytest.approx((144, 72), abs=0.01)]
+    assert get_image_filter(file_bytes) == "/DCTDecode"
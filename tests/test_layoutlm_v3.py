@@ -0,0 +1,33 @@
+from PIL import Image
+
+from docprompt.modeling.layoutlm_v3.schema import LAYOUTLMV3_IMAGE_DPI, layoutlmv3_inputs_from_page
+from docprompt.schema.document import DocumentPage
+from docprompt.schema.operations import PageTextExtractionOutput
+
+
+def test_inputs_use_the_page_image_at_200_dpi(make_document, monkeypatch):
+    dpis = []
+
+    def get_image(self, dpi, **kwargs):
+        dpis.append(dpi)
+
+        return Image.new("L", (10, 10))
+
+    monkeypatch.setattr(DocumentPage, "get_image", get_image)
+
+    document = make_document(["Hello world"])
+    document.text_sidecars = {"provider": {1: PageTextExtractionOutput(text="Hello world")}}
+
+    inputs = layoutlmv3_inputs_from_page(document.get_page(1))
+
+    assert dpis == [LAYOUTLMV3_IMAGE_DPI] == [200]
+    assert inputs.image.mode == "RGB"
+
+
+def test_inputs_use_a_given_image(make_document):
+    document = make_document(["Hello world"])
+    document.text_sidecars = {"provider": {1: PageTextExtractionOutput(text="Hello world")}}
+
+    inputs = layoutlmv3_inputs_from_page(document.get_page(1), image=Image.new("RGB", (20, 20)))
+
+    assert inputs.image.size == (20, 20)
@@ -0,0 +1,113 @@
+from io import BytesIO
+
+import pytest
+from pypdf import PdfReader, PdfWriter
+from pypdf.generic import DecodedStreamObject, NameObject
+
+from docprompt.utils.metadata import OutlineItem, get_document_metadata
+
+XMP = """<?xpacket begin="" id="W5M0MpCehiHzreSzNTczkc9d"?>
+<x:xmpmeta xmlns:x="adobe:ns:meta/">
+  <rdf:RDF xmlns:rdf="http://www.w3.org/1999/02/22-rdf-syntax-ns#">
+    <rdf:Description rdf:about=""
+        xmlns:dc="http://purl.org/dc/elements/1.1/"
+        xmlns:xmp="http://ns.adobe.com/xap/1.0/"
+        xmlns:pdf="http://ns.adobe.com/pdf/1.3/">
+      <dc:title><rdf:Alt><rdf:li xml:lang="x-default">Annual report</rdf:li></rdf:Alt></dc:title>
+      <dc:creator><rdf:Seq><rdf:li>Jane Doe</rdf:li><rdf:li>John Roe</rdf:li></rdf:Seq></dc:creator>
+      <dc:subject><rdf:Bag><rdf:li>finance</rdf:li></rdf:Bag></dc:subject>
+      <xmp:CreatorTool>Report Builder</xmp:CreatorTool>
+      <xmp:CreateDate>2024-01-02T03:04:05Z</xmp:CreateDate>
+      <pdf:Producer>Report Builder PDF</pdf:Producer>
+    </rdf:Description>
+  </rdf:RDF>
+</x:xmpmeta>
+<?xpacket end="w"?>"""
+
+
+@pytest.fixture
+def report_pdf(make_pdf) -> bytes:
+    """
+    A five page report with front matter numbered i and ii, chapters from 1 and an appendix from A-1
+    """
+    writer = PdfWriter(clone_from=PdfReader(BytesIO(make_pdf(["Title", "Preface", "One", "Two", "Appendix"]))))
+
+    writer.add_metadata(
+        {
+            "/Title": "Annual report",
+            "/Author": "Jane Doe",
+            "/CreationDate": "D:20240102030405+00'00'",
+            "/Department": "Finance",
+        }
+    )
+
+    front_matter = writer.add_outline_item("Front matter", 0)
+    writer.add_outline_item("Preface", 1, parent=front_matter)
+    writer.add_outline_item("Chapter 1", 2)
+
+    writer.set_page_label(0, 1, style="/r")
+    writer.set_page_label(2, 3, style="/D", start=1)
+    writer.set_page_label(4, 4, style="/D", prefix="A-", start=1)
+
+    xmp = DecodedStreamObject()
+    xmp.set_data(XMP.encode("utf-8"))
+    xmp.update({NameObject("/Type"): NameObject("/Metadata"), NameObject("/Subtype"): NameObject("/XML")})
+    writer.root_object[NameObject("/Metadata")] = writer.add_object(xmp)
+
+    output_stream = BytesIO()
+    writer.write(output_stream)
+
+    return output_stream.getvalue()
+
+
+def test_info(report_pdf):
+    info = get_document_metadata(report_pdf).info
+
+    assert (info.title, info.author) == ("Annual report", "Jane Doe")
+    assert (info.created_at.year, info.created_at.month, info.created_at.day) == (2024, 1, 2)
+    assert info.custom == {"Department": "Finance"}
+
+
+def test_outline(report_pdf):
+    metadata = get_document_metadata(report_pdf)
+
+    assert metadata.outline == [
+        OutlineItem(
+            title="Front matter",
+            level=1,
+            page_number=1,
+            children=[OutlineItem(title="Preface", level=2, page_number=2)],
+        ),
+        OutlineItem(title="Chapter 1", level=1, page_number=3),
+    ]
+    assert [item.title for item in metadata.iter_outline()] == ["Front matter", "Preface", "Chapter 1"]
+
+
+def test_page_labels(report_pdf):
+    metadata = get_document_metadata(report_pdf)
+
+    assert metadata.page_labels == ["i", "ii", "1", "2", "A-1"]
+    assert metadata.get_table_of_contents() == "Front matter ... i\n  Preface ... ii\nChapter 1 ... 1"
+    assert metadata.get_table_of_contents(include_page_labels=False) == (
+        "Front matter ... 1\n  Preface ... 2\nChapter 1 ... 3"
+    )
+
+
+def test_xmp(report_pdf):
+    xmp = get_document_metadata(report_pdf).xmp
+
+    assert xmp.title == "Annual report"
+    assert xmp.creators == ["Jane Doe", "John Roe"]
+    assert xmp.subjects == ["finance"]
+    assert xmp.creator_tool == "Report Builder"
+    assert xmp.producer == "Report Builder PDF"
+    assert xmp.created_at.year == 2024
+    assert "x:xmpmeta" in xmp.raw
+
+
+def test_plain_pdf(make_pdf):
+    metadata = get_document_metadata(make_pdf(["One", "Two"]))
+
+    assert metadata.outline == []
+    assert metadata.xmp is None
+    assert metadata.page_labels == ["1", "2"]
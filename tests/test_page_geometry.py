@@ -0,0 +1,147 @@
+from io import BytesIO
+
+import pytest
+from pypdf import PdfReader
+
+from docprompt.schema.layout import NormBBox
+from docprompt.schema.page_geometry import PageGeometry
+
+ROTATIONS = [0, 90, 180, 270]
+
+
+def make_geometry(rotation: int = 0, cropbox=(0, 0, 200, 100), user_unit: float = 1.0) -> PageGeometry:
+    return PageGeometry(mediabox=(0, 0, 200, 100), cropbox=cropbox, rotation=rotation, user_unit=user_unit)
+
+
+@pytest.mark.parametrize(
+    "rotation, expected",
+    [
+        # Where the bottom-left corner of the unrotated page ends up on the displayed page
+        (0, (0, 1)),
+        (90, (0, 0)),
+        (180, (1, 0)),
+        (270, (1, 1)),
+    ],
+)
+def test_user_space_to_normalized(rotation, expected):
+    assert make_geometry(rotation).user_space_to_normalized(0, 0) == pytest.approx(expected)
+
+
+@pytest.mark.parametrize("rotation", ROTATIONS)
+def test_normalized_round_trip(rotation):
+    geometry = make_geometry(rotation, cropbox=(20, 10, 180, 90))
+
+    for point in [(0.0, 0.0), (0.25, 0.75), (1.0, 0.5)]:
+        assert geometry.user_space_to_normalized(*geometry.normalized_to_user_space(*point)) == pytest.approx(point)
+
+
+@pytest.mark.parametrize("rotation, size", [(0, (200, 100)), (90, (100, 200)), (180, (200, 100)), (270, (100, 200))])
+def test_displayed_size(rotation, size):
+    geometry = make_geometry(rotation)
+
+    assert (geometry.width, geometry.height) == size
+    assert geometry.get_render_size(144) == (size[0] * 2, size[1] * 2)
+
+
+def test_cropbox_is_the_visible_area():
+    geometry = make_geometry(cropbox=(50, 0, 150, 100))
+
+    assert geometry.width == 100
+    assert geometry.user_space_to_normalized(50, 100) == pytest.approx((0, 0))
+    assert geometry.user_space_to_normalized(150, 0) == pytest.approx((1, 1))
+
+
+def test_user_unit_scales_displayed_size():
+    geometry = make_geometry(user_unit=2.0)
+
+    assert (geometry.width, geometry.height) == (400, 200)
+
+
+@pytest.mark.parametrize("rotation", ROTATIONS)
+def test_bbox_round_trip(rotation):
+    geometry = make_geometry(rotation)
+    bbox = NormBBox(x0=0.1, top=0.2, x1=0.4, bottom=0.6)
+
+    assert geometry.bbox_from_user_space(*geometry.bbox_to_user_space(bbox)).as_tuple() == pytest.approx(
+        bbox.as_tuple()
+    )
+
+
+def test_bbox_from_user_space_clamps():
+    bbox = make_geometry().bbox_from_user_space(-10, -10, 100, 50)
+
+    assert bbox.as_tuple() == pytest.approx((0, 0.5, 0.5, 1))
+
+
+def test_from_pdf_page(make_pdf):
+    pdf_page = PdfReader(BytesIO(make_pdf(["text"], width=200, height=100))).pages[0]
+    pdf_page.rotate(90)
+
+    geometry = PageGeometry.from_pdf_page(pdf_page)
+
+    assert geometry.rotation == 90
+    assert (geometry.width, geometry.height) == (100, 200)
+
+
+LETTER = PageGeometry(mediabox=(0, 0, 612, 792), cropbox=(0, 0, 612, 792))
+
+
+@pytest.mark.parametrize(
+    "limits",
+    [
+        {"max_width": 1000},
+        {"max_height": 1000},
+        {"max_pixels": 1_000_000},
+        {"max_width": 800, "max_height": 2000, "max_pixels": 2_000_000},
+        {"max_width": 1},
+    ],
+)
+def test_get_dpi_for_size_is_the_highest_that_fits(limits):
+    def fits(dpi: int) -> bool:
+        width, height = LETTER.get_render_size(dpi)
+
+        return (
+            width <= limits.get("max_width", width)
+            and height <= limits.get("max_height", height)
+            and width * height <= limits.get("max_pixels", width * height)
+        )
+
+    dpi = LETTER.get_dpi_for_size(**limits)
+
+    assert dpi >= 1
+    assert fits(dpi) or dpi == 1
+    assert not fits(dpi + 1)
+
+
+def test_get_dpi_for_size_accounts_for_rotation():
+    rotated = LETTER.model_copy(update={"rotation": 90})
+
+    assert LETTER.get_dpi_for_size(max_width=1000) == 117
+    assert rotated.get_dpi_for_size(max_width=1000) == 90
+
+
+def test_get_dpi_for_size_requires_a_limit():
+    with pytest.raises(ValueError):
+        LETTER.get_dpi_for_size()
+
+
+@pytest.mark.parametrize("rotation", ROTATIONS)
+def test_bbox_from_pdfplumber_on_an_uncropped_page(rotation):
+    geometry = make_geometry(rotation)
+
+    # pdfplumber measures the displayed (rotated) media box from its top-left corner
+    bbox = geometry.bbox_from_pdfplumber(0, 0, geometry.width / 2, geometry.height / 4)
+
+    assert (bbox.x0, bbox.top, bbox.x1, bbox.bottom) == pytest.approx((0, 0, 0.5, 0.25))
+
+
+@pytest.mark.parametrize("rotation", ROTATIONS)
+def test_bbox_from_pdfplumber_matches_user_space(rotation):
+    geometry = make_geometry(rotation, cropbox=(20, 10, 180, 90))
+    x0, y0 = geometry.layout_to_user_space(30, 40)
+    x1, y1 = geometry.layout_to_user_space(50, 60)
+    layout_height = 100 if rotation in (0, 180) else 200
+
+    bbox = geometry.bbox_from_pdfplumber(30, layout_height - 60, 50, layout_height - 40)
+
+    assert bbox == geometry.bbox_from_user_space(x0, y0, x1, y1)
@@ -0,0 +1,72 @@
+from io import BytesIO
+
+import pdfplumber
+import pytest
+from pypdf import PdfReader, PdfWriter
+from pypdf.generic import RectangleObject
+
+from docprompt.schema.page_geometry import PageGeometry
+from docprompt.service_providers.pdf_plumber import PdfPlumberProvider
+
+
+def transform_page(file_bytes: bytes, cropbox=None, rotation: int = 0) -> bytes:
+    writer = PdfWriter(clone_from=PdfReader(BytesIO(file_bytes)))
+    page = writer.pages[0]
+
+    if cropbox is not None:
+        page.cropbox = RectangleObject(cropbox)
+
+    if rotation:
+        page.rotate(rotation)
+
+    output_stream = BytesIO()
+    writer.write(output_stream)
+
+    return output_stream.getvalue()
+
+
+def extract_words(file_bytes: bytes):
+    with pdfplumber.open(BytesIO(file_bytes)) as pdf:
+        return PdfPlumberProvider().extract_page(pdf.pages[0]).words
+
+
+def test_words_on_a_plain_page(make_pdf):
+    words = extract_words(make_pdf(["Hello world"]))
+
+    assert [word.text for word in words] == ["Hello", "world"]
+    assert words[0].bounding_box.x0 == pytest.approx(72 / 612)
+    assert 0 < words[0].bounding_box.top < words[0].bounding_box.bottom < 100 / 792
+
+
+@pytest.mark.parametrize(
+    "cropbox, rotation",
+    [
+        ((36, 396, 576, 792), 0),
+        (None, 90),
+        ((36, 396, 576, 792), 90),
+        (None, 180),
+        ((0, 300, 400, 792), 270),
+    ],
+)
+def test_words_line_up_with_the_displayed_page(make_pdf, cropbox, rotation):
+    # A single character, since pdfplumber orders the characters of rotated text by position
+    file_bytes = make_pdf(["H"])
+    transformed = transform_page(file_bytes, cropbox=cropbox, rotation=rotation)
+
+    # The same words, mapped from the plain page to user space and onto the transformed page
+    plain_geometry = PageGeometry.from_bytes(file_bytes, 1)
+    geometry = PageGeometry.from_bytes(transformed, 1)
+    expected = [
+        geometry.bbox_from_user_space(*plain_geometry.bbox_to_user_space(word.bounding_box))
+        for word in extract_words(file_bytes)
+    ]
+
+    words = extract_words(transformed)
+
+    assert [word.text for word in words] == ["H"]
+
+    bbox = words[0].bounding_box
+
+    assert (bbox.x0, bbox.top, bbox.x1, bbox.bottom) == pytest.approx(
+        (expected[0].x0, expected[0].top, expected[0].x1, expected[0].bottom), abs=1e-4
+    )
@@ -0,0 +1,102 @@
+import os
+import time
+from typing import Dict, List, Optional
+
+import pytest
+
+from docprompt.rasterizers.base import BaseRasterizer
+from docprompt.rasterizers.cache import CachedRasterizer, RasterCache
+
+KEY = {"dpi": 100, "device": "png16m", "rasterizer": "test"}
+
+
+class CountingRasterizer(BaseRasterizer):
+    name = "test"
+
+    def __init__(self):
+        self.calls: List[Optional[List[int]]] = []
+
+    def is_available(self) -> bool:
+        return True
+
+    def _rasterize(self, fp, pages, *, dpi, device, downscale_factor, quality) -> Dict[int, bytes]:
+        self.calls.append(pages)
+
+        return {page_number: b"x" * 100 for page_number in pages}
+
+
+def test_get_and_put(tmp_path):
+    cache = RasterCache(tmp_path)
+
+    assert cache.get("hash", 1, **KEY) is None
+
+    cache.put("hash", 1, b"raster", **KEY)
+
+    assert cache.get("hash", 1, **KEY) == b"raster"
+    assert cache.get("hash", 1, **{**KEY, "dpi": 200}) is None
+    assert cache.size() == len(b"raster")
+
+
+def test_evicts_least_recently_used(tmp_path):
+    cache = RasterCache(tmp_path, max_size=250)
+
+    for page_number in [1, 2, 3]:
+        cache.put("hash", page_number, b"x" * 100, **KEY)
+
+        # Modification times are the only record of use, so make sure they differ
+        path = cache.get_path("hash", page_number, **KEY)
+        os.utime(path, (time.time() - 10 + page_number, time.time() - 10 + page_number))
+
+    cache.get("hash", 1, **KEY)  # Page 1 is now the most recently used
+    cache.evict()
+
+    assert cache.get("hash", 1, **KEY) is not None
+    assert cache.get("hash", 2, **KEY) is None
+    assert cache.get("hash", 3, **KEY) is not None
+
+
+def test_evict_if_full_only_lists_the_cache_when_needed(tmp_path, monkeypatch):
+    cache = RasterCache(tmp_path, max_size=1000)
+    listings = []
+    list_cache = cache._list
+
+    def counting_list():
+        listings.append(1)
+
+        return list_cache()
+
+    monkeypatch.setattr(cache, "_list", counting_list)
+
+    for page_number in range(1, 10):
+        cache.put("hash", page_number, b"x" * 100, **KEY)
+        cache.evict_if_full()
+
+    assert len(listings) == 1
+
+    cache.put("hash", 10, b"x" * 200, **KEY)
+    cache.evict_if_full()
+
+    assert len(listings) == 2
+    assert cache.size() <= 900
+
+
+def test_cached_rasterizer_only_renders_missing_pages(tmp_path):
+    rasterizer = CountingRasterizer()
+    cache = RasterCache(tmp_path)
+    cached = CachedRasterizer(rasterizer, cache, "hash", page_count=3)
+
+    assert cached.rasterize_pdf("document.pdf", pages=[1, 2], dpi=100, device="png16m") == {
+        1: b"x" * 100,
+        2: b"x" * 100,
+    }
+    assert sorted(cached.rasterize_pdf("document.pdf", dpi=100, device="png16m")) == [1, 2, 3]
+    assert rasterizer.calls == [[1, 2], [3]]
+
+
+@pytest.mark.parametrize("max_size", [None, 10**9])
+def test_evict_if_full_keeps_everything_under_the_limit(tmp_path, max_size):
+    cache = RasterCache(tmp_path, max_size=max_size)
+    cache.put("hash", 1, b"raster", **KEY)
+    cache.evict_if_full()
+
+    assert cache.get("hash", 1, **KEY) == b"raster"
@@ -0,0 +1,104 @@
+from io import BytesIO
+
+import pytest
+from PIL import Image
+
+from docprompt._exec import pool as pool_module
+from docprompt._exec.ghostscript import DEFAULT_TIMEOUT
+from docprompt._exec.pool import GhostscriptPool
+from docprompt.rasterizers import GhostscriptRasterizer, get_rasterizer
+from docprompt.rasterizers.base import SUPPORTED_DEVICES
+
+
+def test_rasterizers_are_shared_by_name():
+    assert get_rasterizer("ghostscript") is get_rasterizer("ghostscript")
+    assert get_rasterizer("pdftoppm") is not get_rasterizer("ghostscript")
+
+
+def test_rasterizer_instances_are_used_as_is():
+    rasterizer = GhostscriptRasterizer(max_workers=2)
+
+    assert get_rasterizer(rasterizer) is rasterizer
+
+
+def test_unknown_rasterizer():
+    with pytest.raises(ValueError):
+        get_rasterizer("unknown")
+
+
+def test_ghostscript_has_a_timeout_by_default():
+    rasterizer = GhostscriptRasterizer()
+
+    assert rasterizer.limits.timeout == DEFAULT_TIMEOUT
+    assert rasterizer.pool.timeout == DEFAULT_TIMEOUT
+    assert rasterizer.pool.max_workers >= 1
+
+
+@pytest.mark.parametrize(
+    "max_workers, pages, shards",
+    [
+        (2, range(1, 21), [(1, 10), (11, 20)]),
+        (4, range(1, 6), [(1, 4), (5, 5)]),
+        (4, [1, 2, 3, 10, 11], [(1, 3), (10, 11)]),
+        (1, range(1, 9), [(1, 8)]),
+    ],
+)
+def test_shard(max_workers, pages, shards):
+    with GhostscriptPool(max_workers) as pool:
+        assert pool._shard(pages) == shards
+
+
+def test_rasterize_pages_merges_shards(monkeypatch):
+    def rasterize_pdf_to_bytes(fp, *, first_page, last_page, **kwargs):
+        return {page_number: b"%d" % page_number for page_number in range(first_page, last_page + 1)}
+
+    monkeypatch.setattr(pool_module, "rasterize_pdf_to_bytes", rasterize_pdf_to_bytes)
+
+    with GhostscriptPool(2, min_pages_per_shard=1) as pool:
+        assert pool.rasterize_pages("document.pdf", [4, 1, 2, 3]) == {1: b"1", 2: b"2", 3: b"3", 4: b"4"}
+
+
+def test_rasterize_pages_cancels_other_shards_on_error(monkeypatch):
+    cancel_events = []
+
+    def rasterize_pdf_to_bytes(fp, *, first_page, last_page, limits, **kwargs):
+        cancel_events.append(limits.cancel_event)
+
+        if first_page == 1:
+            raise RuntimeError("Ghostscript failed")
+
+        return {}
+
+    monkeypatch.setattr(pool_module, "rasterize_pdf_to_bytes", rasterize_pdf_to_bytes)
+
+    with GhostscriptPool(2, min_pages_per_shard=1) as pool:
+        with pytest.raises(RuntimeError):
+            pool.rasterize_pages("document.pdf", [1, 2])
+
+    assert all(event.is_set() for event in cancel_events)
+
+
+def test_rasterize_pages_rejects_invalid_pages():
+    with GhostscriptPool(2) as pool:
+        with pytest.raises(ValueError):
+            pool.rasterize_pages("document.pdf", [0])
+
+
+def describe_raster(raster: bytes):
+    image = Image.open(BytesIO(raster))
+
+    return image.format, image.mode, image.size, image.info.get("compression")
+
+
+@pytest.mark.parametrize("device", SUPPORTED_DEVICES)
+@pytest.mark.parametrize("name", ["pdftoppm", "pdfium"])
+def test_output_matches_ghostscript(make_document, name, device):
+    for rasterizer_name in ["ghostscript", name]:
+        if not get_rasterizer(rasterizer_name).is_available():
+            pytest.skip(f"The {rasterizer_name} rasterizer is not available")
+
+    document = make_document(["Hello"])
+
+    expected = describe_raster(document.rasterize_page(1, dpi=72, device=device, rasterizer="ghostscript"))
+
+    assert describe_raster(document.rasterize_page(1, dpi=72, device=device, rasterizer=name)) == expected
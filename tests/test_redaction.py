@@ -0,0 +1,59 @@
+import shutil
+from io import BytesIO
+
+import pytest
+from pypdf import PdfReader
+
+from docprompt.masking.redaction import REDACTION_CHARACTER, find_pattern_matches, redact_page_text
+from docprompt.schema.layout import NormBBox
+
+
+@pytest.fixture
+def page_text(make_page_text):
+    return make_page_text(
+        [
+            ("Account", (0.1, 0.1, 0.25, 0.13)),
+            ("12345", (0.27, 0.1, 0.37, 0.13)),
+            ("open", (0.39, 0.1, 0.47, 0.13)),
+        ]
+    )
+
+
+def test_redact_page_text_masks_covered_words(page_text):
+    redacted = redact_page_text(page_text, [NormBBox(x0=0.26, top=0.09, x1=0.38, bottom=0.14)])
+
+    assert redacted.text == f"Account {REDACTION_CHARACTER * 5} open"
+    assert [word.text for word in redacted.words] == ["Account", REDACTION_CHARACTER * 5, "open"]
+    assert redacted.lines[0].text == redacted.text
+
+
+def test_redact_page_text_ignores_slightly_overlapping_words(page_text):
+    redacted = redact_page_text(page_text, [NormBBox(x0=0.36, top=0.09, x1=0.38, bottom=0.14)])
+
+    assert redacted.text == page_text.text
+
+
+def test_redact_page_text_leaves_original_untouched(page_text):
+    redact_page_text(page_text, [NormBBox(x0=0.0, top=0.0, x1=1.0, bottom=1.0)])
+
+    assert page_text.text == "Account 12345 open"
+
+
+def test_find_pattern_matches(page_text):
+    assert [word.text for word in find_pattern_matches(page_text, [r"\d{5}"])] == ["12345"]
+    assert [word.text for word in find_pattern_matches(page_text, [r"t 1"])] == ["Account", "12345"]
+
+
+@pytest.mark.skipif(shutil.which("gs") is None, reason="Ghostscript is not installed")
+def test_redact_document_removes_text_layer(make_document, page_text):
+    document = make_document(["Account 12345 open", "Untouched"])
+    document.text_sidecars = {"provider": {1: page_text}}
+
+    redacted = document.redact(patterns=[r"\d{5}"], dpi=72)
+
+    reader = PdfReader(BytesIO(redacted.file_bytes))
+
+    assert "12345" not in reader.pages[0].extract_text()
+    assert "Untouched" in reader.pages[1].extract_text()
+    assert "12345" not in redacted.text_sidecars["provider"][1].text
+    assert redacted.pages[0].width == pytest.approx(document.pages[0].width)
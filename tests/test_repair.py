@@ -0,0 +1,51 @@
+from io import BytesIO
+
+from pypdf import PdfReader
+
+from docprompt.utils.forms import get_form_fields
+from docprompt.utils.repair import repair_pdf_bytes
+
+
+def break_startxref(file_bytes: bytes) -> bytes:
+    """
+    Points startxref at the header, so readers have to rebuild the xref table by scanning the file
+    """
+    head, _, _ = file_bytes.rpartition(b"startxref")
+
+    return head + b"startxref\n9\n%%EOF\n"
+
+
+def test_clean_files_are_not_rewritten(make_pdf):
+    file_bytes = make_pdf(["Hello"])
+
+    repaired, report = repair_pdf_bytes(file_bytes)
+
+    assert repaired == file_bytes
+    assert not report.needed_repair
+    assert report.strategy is None
+
+
+def test_harmless_warnings_do_not_trigger_a_repair(make_pdf):
+    # A repeated /Type key, swapped in for /Subtype so that no offsets move
+    file_bytes = make_pdf(["Hello"]).replace(b"/Subtype /Type1", b"/Type /Type1   ", 1)
+
+    repaired, report = repair_pdf_bytes(file_bytes)
+
+    assert repaired == file_bytes
+    assert not report.needed_repair
+    assert any("Multiple definitions" in warning for warning in report.warnings)
+
+
+def test_rewrite_keeps_the_outline_and_form_fields(make_form_pdf):
+    file_bytes = break_startxref(make_form_pdf(outline=["Introduction", "Terms"]))
+
+    repaired, report = repair_pdf_bytes(file_bytes)
+
+    assert report.needed_repair
+    assert any("startxref" in problem for problem in report.problems)
+    assert report.strategy == "pypdf_rewrite"
+
+    reader = PdfReader(BytesIO(repaired))
+
+    assert [item.title for item in reader.outline] == ["Introduction", "Terms"]
+    assert [field.name for field in get_form_fields(repaired)] == ["name", "agree", "color"]
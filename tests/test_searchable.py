@@ -0,0 +1,39 @@
+from io import BytesIO
+
+import pdfplumber
+import pytest
+
+from docprompt.service_providers.pdf_plumber import PdfPlumberProvider
+from docprompt.utils.searchable import make_searchable_pdf
+
+WORDS = [("Hello", (0.1, 0.2, 0.3, 0.25)), ("world", (0.35, 0.2, 0.55, 0.25)), ("again", (0.1, 0.5, 0.5, 0.6))]
+
+
+def test_text_layer_words_come_back_where_they_were_given(make_document, make_page_text):
+    document = make_document([""])
+    document.text_sidecars = {"ocr": {1: make_page_text(WORDS)}}
+
+    searchable = make_searchable_pdf(document)
+
+    with pdfplumber.open(BytesIO(searchable.file_bytes)) as pdf:
+        words = PdfPlumberProvider().extract_page(pdf.pages[0]).words
+
+    assert [word.text for word in words] == [text for text, _ in WORDS]
+
+    for word, (_, bbox) in zip(words, WORDS):
+        box = word.bounding_box
+
+        # Glyph boxes come from the font's ascent and descent, so they're a fraction of a point off vertically
+        assert (box.x0, box.top, box.x1, box.bottom) == pytest.approx(bbox, abs=0.002)
+
+
+def test_requires_a_text_sidecar(make_document, make_page_text):
+    document = make_document(["text"])
+
+    with pytest.raises(ValueError):
+        make_searchable_pdf(document)
+
+    document.text_sidecars = {"ocr": {1: make_page_text(WORDS)}}
+
+    with pytest.raises(ValueError):
+        make_searchable_pdf(document, "missing")
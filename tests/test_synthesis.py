@@ -0,0 +1,52 @@
+import pytest
+
+from docprompt.masking.synthesis import synthesize_page_text
+from docprompt.schema.layout import Geometry, NormBBox, TextBlock
+
+
+@pytest.fixture
+def page_text(make_page_text):
+    return make_page_text(
+        [
+            ("Name:", (0.1, 0.1, 0.2, 0.13)),
+            ("John", (0.22, 0.1, 0.3, 0.13)),
+            ("Q", (0.32, 0.1, 0.34, 0.13)),
+            ("Smith", (0.36, 0.1, 0.46, 0.13)),
+            ("here", (0.48, 0.1, 0.56, 0.13)),
+        ]
+    )
+
+
+def make_region(x0: float, x1: float) -> TextBlock:
+    return TextBlock(
+        text="", type="block", geometry=Geometry(bounding_box=NormBBox(x0=x0, top=0.09, x1=x1, bottom=0.14))
+    )
+
+
+def get_span_texts(page_text):
+    return [page_text.text[word.text_spans[0].start_index : word.text_spans[0].end_index] for word in page_text.words]
+
+
+def test_tokens_replace_words_one_to_one(page_text):
+    synthesized = synthesize_page_text(page_text, [(make_region(0.21, 0.47), "Alice B Jones")])
+
+    assert synthesized.text == "Name: Alice B Jones here"
+    assert [word.text for word in synthesized.words] == ["Name:", "Alice", "B", "Jones", "here"]
+    assert get_span_texts(synthesized) == [word.text for word in synthesized.words]
+    assert synthesized.lines[0].text == synthesized.text
+
+
+def test_merged_words_are_removed(page_text):
+    synthesized = synthesize_page_text(page_text, [(make_region(0.21, 0.47), "Alice Jones")])
+
+    assert synthesized.text == "Name: Alice Jones here"
+    assert [word.text for word in synthesized.words] == ["Name:", "Alice Jones", "here"]
+    assert get_span_texts(synthesized) == [word.text for word in synthesized.words]
+    assert synthesized.lines[0].text == synthesized.text
+
+
+def test_uncovered_words_are_kept(page_text):
+    synthesized = synthesize_page_text(page_text, [(make_region(0.6, 0.7), "Alice")])
+
+    assert synthesized.text == page_text.text
+    assert [word.text for word in synthesized.words] == [word.text for word in page_text.words]